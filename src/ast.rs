@@ -3,6 +3,7 @@
 //! have a useful shared representation of various math typesetting approaches. This means that, for
 //! instance, free variables are fine.
 
+use crate::delimiter::DelimKind;
 use crate::operators::Op;
 
 /// An abstract syntax tree representing a mathematical expression.
@@ -10,14 +11,277 @@ use crate::operators::Op;
 pub enum AST {
     /// A generic symbol.
     Sym(Symbol),
-    /// A number literal, represented as a string.
-    Number(String),
+    /// A number literal, like `12.5`, `1.2e-3`, or `0.(3)`.
+    Number(Number),
+    /// A whole number and a fraction of whole numbers, like the mixed number `1 1/2`: the whole
+    /// part, then the numerator and the denominator. A vulgar fraction like `½` is one without the
+    /// whole part.
+    MixedNumber(Option<String>, String, String),
     /// A binary expression with two elements.
     BinaryExpr(BinaryOp, Box<AST>, Box<AST>),
     /// A unary expression with a single element.
     UnaryExpr(UnaryOp, Box<AST>),
     /// A function with a name and an arbitrary number of arguments.
     Function(Symbol, Vec<AST>),
+    /// A big operator like a sum or an integral, with optional lower and upper bounds and then
+    /// the body it applies to.
+    BigOp(Symbol, Option<Box<AST>>, Option<Box<AST>>, Box<AST>),
+    /// A limit: the variable, the value it approaches, the side it approaches from if the limit is
+    /// one-sided, and the body.
+    Limit(Box<AST>, Box<AST>, Option<Direction>, Box<AST>),
+    /// A derivative: its kind, its order, the expression being differentiated, and the variable
+    /// it's differentiated with respect to. `dy/dx` and `d/dx y` are the same thing.
+    Derivative(DerivativeKind, u32, Box<AST>, Box<AST>),
+    /// An expression with prime marks, like `f''`, along with how many there are.
+    Prime(Box<AST>, u32),
+    /// A chain of relations like `0 < x ≤ 1`: the first operand, and then each relation with the
+    /// operand after it. Two operands with a single relation are a normal binary expression.
+    Chain(Box<AST>, Vec<(Op, AST)>),
+    /// A quantifier like ∀: the quantifier symbol, the variable it binds along with anything said
+    /// about it (as in `∀x > 0`), and the statement it applies to.
+    Quantifier(Symbol, Box<AST>, Box<AST>),
+    /// A set written out as a list of its elements, like `{1, 2, 3}`.
+    Set(Vec<AST>),
+    /// A set in set-builder notation, like `{x ∈ ℝ | x > 0}`: the members, and the condition they
+    /// satisfy.
+    SetBuilder(Box<AST>, Box<AST>),
+    /// An expression between a pair of delimiters that mean something on their own, like the
+    /// absolute value `|x|`, along with the kind of delimiter.
+    Fenced(DelimKind, Box<AST>),
+    /// An expression grouped by the delimiters written around it, like the parentheses in
+    /// `(a + b) c` or the brackets in `[a + b] c`, along with the kind of delimiter. Formatters can
+    /// keep these as written, or drop them and add back only the parentheses that are needed.
+    Group(DelimKind, Box<AST>),
+    /// A matrix, given as its rows, which all have the same length. A vector is a matrix with a
+    /// single column, like `[1; 2; 3]`.
+    Matrix(Vec<Vec<AST>>),
+    /// A piecewise function, given as its cases: each value along with the condition for it, or
+    /// None for the case that applies otherwise.
+    Piecewise(Vec<(AST, Option<AST>)>),
+}
+
+impl AST {
+    /// Splits a differential like `dx` off the end of an integrand, returning the rest of the
+    /// integrand and the differential. Returns None if the integrand doesn't end in one.
+    pub fn split_differential(&self) -> Option<(AST, Symbol)> {
+        match self {
+            AST::BinaryExpr(BinaryOp::Concat, rest, last) => match last.as_ref() {
+                AST::Sym(sym) if is_differential(sym) => Some((*rest.clone(), sym.clone())),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Splits a chain of relations into the comparisons it's made of, all of which have to hold:
+    /// `0 < x ≤ 1` means `0 < x` and `x ≤ 1`. Anything else is a single comparison on its own.
+    pub fn comparisons(&self) -> Vec<AST> {
+        match self {
+            AST::Chain(first, rest) => {
+                let mut left = first.as_ref();
+                rest.iter()
+                    .map(|(op, right)| {
+                        let comparison = AST::BinaryExpr(
+                            BinaryOp::Generic(SymbolBinaryOp {
+                                op: op.clone(),
+                                fixity: Fixity::Infix,
+                            }),
+                            Box::new(left.clone()),
+                            Box::new(right.clone()),
+                        );
+                        left = right;
+                        comparison
+                    })
+                    .collect()
+            }
+            _ => vec![self.clone()],
+        }
+    }
+
+    /// Splits a list written with commas, like the inside of `⟨a, b⟩`, into its elements. Anything
+    /// else is a list of one.
+    pub fn comma_list(&self) -> Vec<&AST> {
+        match self {
+            AST::BinaryExpr(BinaryOp::Generic(SymbolBinaryOp { op, .. }), arg1, arg2)
+                if op.sym == *crate::symbols::COMMA =>
+            {
+                let mut list = arg1.comma_list();
+                list.extend(arg2.comma_list());
+                list
+            }
+            _ => vec![self],
+        }
+    }
+
+    /// Returns whether there's a group anywhere in the tree.
+    pub fn has_groups(&self) -> bool {
+        self.find_groups(false)
+    }
+
+    /// Returns whether there's a group in the tree that `without_plain_groups` would remove.
+    pub fn has_plain_groups(&self) -> bool {
+        self.find_groups(true)
+    }
+
+    /// Removes every group from the tree, keeping what's inside them. Whatever formats the result
+    /// has to add back the parentheses that are needed: `(a + b) c` becomes the product of `a + b`
+    /// and `c`.
+    pub fn without_groups(&self) -> AST {
+        self.strip_groups(false)
+    }
+
+    /// Removes the groups that only group, like `without_groups`, but keeps the parentheses around
+    /// the argument of something applied like a function: without them, `f(x)` would be `fx`.
+    /// Those parentheses mean something on their own, so they're kept as a `Fenced` expression.
+    pub fn without_plain_groups(&self) -> AST {
+        self.strip_groups(true)
+    }
+
+    /// Returns whether there's a group to remove in the tree, skipping the arguments of applications
+    /// if `keep_arguments` is set.
+    fn find_groups(&self, keep_arguments: bool) -> bool {
+        let find = |tree: &AST| tree.find_groups(keep_arguments);
+        match self {
+            AST::Sym(_) | AST::Number(_) | AST::MixedNumber(_, _, _) => false,
+            AST::BinaryExpr(BinaryOp::Concat, arg1, arg2)
+                if keep_arguments && is_application(arg1, arg2) =>
+            {
+                // the parentheses still have to be turned into a fenced expression
+                matches!(arg2.as_ref(), AST::Group(_, _)) || find(arg1) || find(arg2.inner_group())
+            }
+            AST::BinaryExpr(_, arg1, arg2) => find(arg1) || find(arg2),
+            AST::UnaryExpr(_, arg) | AST::Prime(arg, _) | AST::Fenced(_, arg) => find(arg),
+            AST::Function(_, args) | AST::Set(args) => args.iter().any(find),
+            AST::BigOp(_, lower, upper, body) => {
+                [lower, upper]
+                    .into_iter()
+                    .flatten()
+                    .any(|bound| find(bound))
+                    || find(body)
+            }
+            AST::Limit(var, target, _, body) => find(var) || find(target) || find(body),
+            AST::Derivative(_, _, function, var) => find(function) || find(var),
+            AST::Chain(first, rest) => find(first) || rest.iter().any(|(_, operand)| find(operand)),
+            AST::Quantifier(_, binding, body) => find(binding) || find(body),
+            AST::SetBuilder(members, condition) => find(members) || find(condition),
+            AST::Group(_, _) => true,
+            AST::Matrix(rows) => rows.iter().flatten().any(find),
+            AST::Piecewise(cases) => cases
+                .iter()
+                .any(|(value, condition)| find(value) || condition.as_ref().is_some_and(find)),
+        }
+    }
+
+    /// Removes the groups from the tree, except for the arguments of applications if
+    /// `keep_arguments` is set.
+    fn strip_groups(&self, keep_arguments: bool) -> AST {
+        let strip = |tree: &AST| tree.strip_groups(keep_arguments);
+        let boxed = |tree: &AST| Box::new(strip(tree));
+        match self {
+            AST::Sym(_) | AST::Number(_) | AST::MixedNumber(_, _, _) => self.clone(),
+            AST::BinaryExpr(BinaryOp::Concat, arg1, arg2)
+                if keep_arguments && is_application(arg1, arg2) =>
+            {
+                AST::BinaryExpr(
+                    BinaryOp::Concat,
+                    boxed(arg1),
+                    Box::new(AST::Fenced(DelimKind::Paren, boxed(arg2.inner_group()))),
+                )
+            }
+            AST::BinaryExpr(op, arg1, arg2) => {
+                AST::BinaryExpr(op.clone(), boxed(arg1), boxed(arg2))
+            }
+            AST::UnaryExpr(op, arg) => AST::UnaryExpr(op.clone(), boxed(arg)),
+            AST::Function(name, args) => {
+                AST::Function(name.clone(), args.iter().map(strip).collect())
+            }
+            AST::BigOp(op, lower, upper, body) => AST::BigOp(
+                op.clone(),
+                lower.as_deref().map(boxed),
+                upper.as_deref().map(boxed),
+                boxed(body),
+            ),
+            AST::Limit(var, target, direction, body) => {
+                AST::Limit(boxed(var), boxed(target), *direction, boxed(body))
+            }
+            AST::Derivative(kind, order, function, var) => {
+                AST::Derivative(*kind, *order, boxed(function), boxed(var))
+            }
+            AST::Prime(arg, count) => AST::Prime(boxed(arg), *count),
+            AST::Chain(first, rest) => AST::Chain(
+                boxed(first),
+                rest.iter()
+                    .map(|(op, operand)| (op.clone(), strip(operand)))
+                    .collect(),
+            ),
+            AST::Quantifier(quantifier, binding, body) => {
+                AST::Quantifier(quantifier.clone(), boxed(binding), boxed(body))
+            }
+            AST::Set(elements) => AST::Set(elements.iter().map(strip).collect()),
+            AST::SetBuilder(members, condition) => {
+                AST::SetBuilder(boxed(members), boxed(condition))
+            }
+            AST::Fenced(kind, inner) => AST::Fenced(*kind, boxed(inner)),
+            AST::Group(_, inner) => strip(inner),
+            AST::Matrix(rows) => AST::Matrix(
+                rows.iter()
+                    .map(|row| row.iter().map(strip).collect())
+                    .collect(),
+            ),
+            AST::Piecewise(cases) => AST::Piecewise(
+                cases
+                    .iter()
+                    .map(|(value, condition)| (strip(value), condition.as_ref().map(strip)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The inside of a group, however many groups are around it, or the tree itself if it isn't
+    /// one.
+    fn inner_group(&self) -> &AST {
+        match self {
+            AST::Group(_, inner) => inner.inner_group(),
+            tree => tree,
+        }
+    }
+
+    /// Takes the parentheses off an expression that doesn't need them to be grouped, like the
+    /// numerator of a fraction or a script: `(a + b) / 2` is the fraction of `a + b` and `2`, and
+    /// the parentheses only mark where the numerator ends.
+    pub fn ungroup_parens(self) -> AST {
+        match self {
+            AST::Group(DelimKind::Paren, inner) => *inner,
+            tree => tree,
+        }
+    }
+}
+
+/// Returns whether a juxtaposition is something applied to an argument in parentheses, like `f(x)`,
+/// `f'(x)` or `f_1(x)`, where the parentheses are what make it read as an application. The
+/// parentheses can be a group as parsed, or fenced as `without_plain_groups` leaves them.
+pub fn is_application(left: &AST, right: &AST) -> bool {
+    let applied = match left {
+        AST::Sym(_) | AST::Prime(_, _) => true,
+        AST::BinaryExpr(BinaryOp::Subscript | BinaryOp::Power, base, _) => {
+            matches!(base.as_ref(), AST::Sym(_))
+        }
+        _ => false,
+    };
+    applied
+        && matches!(
+            right,
+            AST::Group(DelimKind::Paren, _) | AST::Fenced(DelimKind::Paren, _)
+        )
+}
+
+/// Returns whether a symbol is a differential: a d followed by a single letter, like `dx` or `dθ`.
+fn is_differential(sym: &Symbol) -> bool {
+    let mut chars = sym.unicode_repr.chars();
+    matches!(
+        (chars.next(), chars.next(), chars.next()),
+        (Some('d'), Some(c), None) if c.is_alphabetic()
+    )
 }
 
 /// A generic symbol. Can have multiple different representations, with a preferred one
@@ -68,12 +332,55 @@ impl Symbol {
     /// Given a string, returns a matched prefix of that string if the prefix matches one of the
     /// operator's representations and None otherwise.
     pub fn match_front(&self, input: &str) -> Option<&str> {
-        for repr in self.reprs() {
-            if input.starts_with(repr) {
-                return Some(repr);
-            }
+        self.reprs()
+            .into_iter()
+            .find(|&repr| input.starts_with(repr))
+    }
+}
+
+/// A number literal, split into the parts that are written differently in each format.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Number {
+    /// The digits, with a `.` for the decimal point if there is one: the `1.2` in `1.2e-3`.
+    pub mantissa: String,
+
+    /// The digits that repeat forever after the mantissa, for repeating decimals: the `3` in
+    /// `0.(3)`, or the `6` in `0.1(6)`. The mantissa always has a decimal point then, even if
+    /// nothing comes after it, like the `0.` in `0.(3)`.
+    pub repeating: Option<String>,
+
+    /// The power of ten the mantissa is multiplied by, for numbers in scientific notation: the
+    /// `-3` in `1.2e-3`. A negative exponent starts with an ASCII `-`.
+    pub exponent: Option<String>,
+}
+
+impl Number {
+    /// Creates a number in scientific notation.
+    pub fn scientific(mantissa: &str, exponent: &str) -> Number {
+        Number {
+            mantissa: mantissa.into(),
+            repeating: None,
+            exponent: Some(exponent.into()),
+        }
+    }
+
+    /// Creates a repeating decimal.
+    pub fn repeating(mantissa: &str, repeating: &str) -> Number {
+        Number {
+            mantissa: mantissa.into(),
+            repeating: Some(repeating.into()),
+            exponent: None,
+        }
+    }
+}
+
+impl From<&str> for Number {
+    fn from(mantissa: &str) -> Self {
+        Number {
+            mantissa: mantissa.into(),
+            repeating: None,
+            exponent: None,
         }
-        None
     }
 }
 
@@ -106,18 +413,43 @@ pub enum BinaryOp {
     Generic(SymbolBinaryOp),
     /// Exponentiation.
     Power,
+    /// A subscript, like the 1 in `x_1`. Combined with `Power` this also covers `x_i^2`, which is
+    /// the power of the subscripted base.
+    Subscript,
     /// Division, represented using fractions when possible.
     Frac,
     /// A logarithm with a specific base.
     Log,
+    /// A root, with the index first: the square root of x has index 2.
+    Root,
     /// Concatenation, represented with no operator at all.
     Concat,
 }
 
+/// The side that a one-sided limit approaches from.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Direction {
+    /// From above, written like `0^+`.
+    Above,
+    /// From below, written like `0^-`.
+    Below,
+}
+
+/// Whether a derivative is ordinary or partial.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum DerivativeKind {
+    /// An ordinary derivative, written with d.
+    Ordinary,
+    /// A partial derivative, written with ∂.
+    Partial,
+}
+
 /// A unary operator. For simple ones like the logical not and unary minus/plus, this is just a
 /// symbol. In the future there might be more complex examples with custom parsing.
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub enum UnaryOp {
     /// A generic unary operator with a given symbol.
     Generic(Symbol),
+    /// A postfix operator with a given symbol, written after its argument, like the factorial.
+    Postfix(Symbol),
 }
@@ -21,6 +21,28 @@ pub enum DelimKind {
 
     /// A bracket: []
     Bracket,
+
+    /// A brace, which groups the elements of a set: {}
+    Brace,
+
+    /// An absolute value: ||
+    Abs,
+
+    /// A norm: ‖‖
+    Norm,
+
+    /// A floor: ⌊⌋, or floor() in plain text
+    Floor,
+
+    /// A ceiling: ⌈⌉, or ceil() in plain text
+    Ceil,
+
+    /// Angle brackets, as in an inner product: ⟨⟩, or << >> in plain text
+    Angle,
+
+    /// An invisible group, like the braces in LaTeX's `x^{2}`. These are never matched in plain
+    /// text, so they can only come from input formats that have them.
+    Group,
 }
 
 /// A delimiter with a symbol that can either be left or right.
@@ -46,10 +68,40 @@ impl Delimiter {
             (DelimDir::Left, DelimKind::Bracket) => symbols::LEFT_BRACKET.clone(),
             (DelimDir::Right, DelimKind::Paren) => symbols::RIGHT_PAR.clone(),
             (DelimDir::Right, DelimKind::Bracket) => symbols::RIGHT_BRACKET.clone(),
+            (DelimDir::Left, DelimKind::Brace) => symbols::LEFT_BRACE.clone(),
+            (DelimDir::Right, DelimKind::Brace) => symbols::RIGHT_BRACE.clone(),
+            (DelimDir::Left, DelimKind::Abs) => symbols::LEFT_ABS.clone(),
+            (DelimDir::Right, DelimKind::Abs) => symbols::RIGHT_ABS.clone(),
+            (DelimDir::Left, DelimKind::Norm) => symbols::LEFT_NORM.clone(),
+            (DelimDir::Right, DelimKind::Norm) => symbols::RIGHT_NORM.clone(),
+            (DelimDir::Left, DelimKind::Floor) => symbols::LEFT_FLOOR.clone(),
+            (DelimDir::Right, DelimKind::Floor) => symbols::RIGHT_FLOOR.clone(),
+            (DelimDir::Left, DelimKind::Ceil) => symbols::LEFT_CEIL.clone(),
+            (DelimDir::Right, DelimKind::Ceil) => symbols::RIGHT_CEIL.clone(),
+            (DelimDir::Left, DelimKind::Angle) => symbols::LEFT_ANGLE.clone(),
+            (DelimDir::Right, DelimKind::Angle) => symbols::RIGHT_ANGLE.clone(),
+            (DelimDir::Left, DelimKind::Group) => symbols::LEFT_GROUP.clone(),
+            (DelimDir::Right, DelimKind::Group) => symbols::RIGHT_GROUP.clone(),
         }
     }
 }
 
+/// Gets the symbols for the left and right delimiters of a kind.
+pub fn pair(kind: DelimKind) -> (Symbol, Symbol) {
+    (
+        Delimiter {
+            dir: DelimDir::Left,
+            kind,
+        }
+        .get_symbol(),
+        Delimiter {
+            dir: DelimDir::Right,
+            kind,
+        }
+        .get_symbol(),
+    )
+}
+
 pub static LPAR: Delimiter = Delimiter {
     dir: DelimDir::Left,
     kind: DelimKind::Paren,
@@ -67,4 +119,73 @@ pub static RBRACKET: Delimiter = Delimiter {
     kind: DelimKind::Bracket,
 };
 
-pub static DELIMS: [Delimiter; 4] = [LPAR, RPAR, LBRACKET, RBRACKET];
+pub static LBRACE: Delimiter = Delimiter {
+    dir: DelimDir::Left,
+    kind: DelimKind::Brace,
+};
+pub static RBRACE: Delimiter = Delimiter {
+    dir: DelimDir::Right,
+    kind: DelimKind::Brace,
+};
+
+pub static LABS: Delimiter = Delimiter {
+    dir: DelimDir::Left,
+    kind: DelimKind::Abs,
+};
+pub static RABS: Delimiter = Delimiter {
+    dir: DelimDir::Right,
+    kind: DelimKind::Abs,
+};
+pub static LNORM: Delimiter = Delimiter {
+    dir: DelimDir::Left,
+    kind: DelimKind::Norm,
+};
+pub static RNORM: Delimiter = Delimiter {
+    dir: DelimDir::Right,
+    kind: DelimKind::Norm,
+};
+
+pub static LFLOOR: Delimiter = Delimiter {
+    dir: DelimDir::Left,
+    kind: DelimKind::Floor,
+};
+pub static RFLOOR: Delimiter = Delimiter {
+    dir: DelimDir::Right,
+    kind: DelimKind::Floor,
+};
+pub static LCEIL: Delimiter = Delimiter {
+    dir: DelimDir::Left,
+    kind: DelimKind::Ceil,
+};
+pub static RCEIL: Delimiter = Delimiter {
+    dir: DelimDir::Right,
+    kind: DelimKind::Ceil,
+};
+pub static LANGLE: Delimiter = Delimiter {
+    dir: DelimDir::Left,
+    kind: DelimKind::Angle,
+};
+pub static RANGLE: Delimiter = Delimiter {
+    dir: DelimDir::Right,
+    kind: DelimKind::Angle,
+};
+
+pub static LGROUP: Delimiter = Delimiter {
+    dir: DelimDir::Left,
+    kind: DelimKind::Group,
+};
+pub static RGROUP: Delimiter = Delimiter {
+    dir: DelimDir::Right,
+    kind: DelimKind::Group,
+};
+
+/// The delimiters that can be written directly in plain text. The plain-text floor and ceiling
+/// close with a `)`, so they come after the parentheses: a `)` on its own is always a `RPAR`, and
+/// it's up to the tokenizer to figure out what it closes.
+pub static DELIMS: [Delimiter; 12] = [
+    LPAR, RPAR, LBRACKET, RBRACKET, LBRACE, RBRACE, LFLOOR, RFLOOR, LCEIL, RCEIL, LANGLE, RANGLE,
+];
+
+/// The delimiters written with the same bar on both sides. In plain text, whether one of these opens
+/// or closes depends on what comes before it, so they're matched separately from `DELIMS`.
+pub static BARS: [Delimiter; 4] = [LABS, RABS, LNORM, RNORM];
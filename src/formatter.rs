@@ -3,39 +3,187 @@
 //! structure of data, not how that structure is represented.
 
 use crate::ast::*;
+use crate::delimiter::DelimKind;
+use crate::operators::Op;
+
+/// How a formatter deals with the delimiters that group expressions in the input, like the
+/// parentheses in `(a + b) c` or `[(a + b) c]^2`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Grouping {
+    /// Drops the groups and uses parentheses only where they're needed to read correctly.
+    #[default]
+    Minimal,
+    /// Keeps every group as it was written, with the same kind of delimiter, even if it isn't
+    /// needed.
+    Preserve,
+}
+
+/// How a formatter writes the digits of a number, which differs between languages.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct NumberStyle {
+    /// Whether the decimal point is a comma, as in `3,14`.
+    pub decimal_comma: bool,
+    /// Whether long runs of digits are split into groups of three with thin spaces, as in
+    /// `1 000 000` or `3.141 59`. Runs of four digits or fewer stay together.
+    pub group_digits: bool,
+}
+
+impl NumberStyle {
+    /// Writes the digits of a mantissa like `1234.5`, given how this format writes a decimal comma
+    /// and a thin space.
+    pub fn format_mantissa(&self, mantissa: &str, comma: &str, thin_space: &str) -> String {
+        let (integer, fraction) = match mantissa.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (mantissa, None),
+        };
+        // the whole part is grouped from the right, and the fraction from the left
+        let group = |digits: &str, from_right: bool| {
+            if !self.group_digits || digits.len() <= 4 {
+                return digits.to_string();
+            }
+            let offset = if from_right { digits.len() % 3 } else { 0 };
+            let mut grouped = String::new();
+            for (i, c) in digits.chars().enumerate() {
+                if i > 0 && (i + 3 - offset) % 3 == 0 {
+                    grouped.push_str(thin_space);
+                }
+                grouped.push(c);
+            }
+            grouped
+        };
+        let mut output = group(integer, true);
+        if let Some(fraction) = fraction {
+            output.push_str(if self.decimal_comma { comma } else { "." });
+            output.push_str(&group(fraction, false));
+        }
+        output
+    }
+}
 
 /// A serializer for `AST`s, controlling how they are displayed to a specific output type T.
 pub trait Formatter {
     type Output;
 
+    /// How this formatter deals with groups.
+    fn grouping(&self) -> Grouping;
+
     /// Formats a symbol.
     fn format_symbol(&mut self, sym: &Symbol) -> Self::Output;
 
-    /// Formats a number literal, given as a string.
-    fn format_number(&mut self, dec: &str) -> Self::Output;
+    /// Formats a number literal.
+    fn format_number(&mut self, num: &Number) -> Self::Output;
 
-    /// Formats a binary expression with two arguments.
-    fn format_binary_expr(
+    /// Formats a mixed number like 1 1/2, or a vulgar fraction like ½ if there's no whole part.
+    fn format_mixed_number(
         &mut self,
-        op: &BinaryOp,
-        arg1: &Box<AST>,
-        arg2: &Box<AST>,
+        whole: Option<&str>,
+        numerator: &str,
+        denominator: &str,
     ) -> Self::Output;
 
+    /// Formats a binary expression with two arguments.
+    fn format_binary_expr(&mut self, op: &BinaryOp, arg1: &AST, arg2: &AST) -> Self::Output;
+
     /// Formats a unary expression with one argument.
-    fn format_unary_expr(&mut self, op: &UnaryOp, arg: &Box<AST>) -> Self::Output;
+    fn format_unary_expr(&mut self, op: &UnaryOp, arg: &AST) -> Self::Output;
 
     /// Formats a function with a name and an arbitrary number of arguments.
-    fn format_function(&mut self, name: &Symbol, args: &Vec<AST>) -> Self::Output;
+    fn format_function(&mut self, name: &Symbol, args: &[AST]) -> Self::Output;
+
+    /// Formats a big operator, like a sum or an integral, with its bounds and body.
+    fn format_big_op(
+        &mut self,
+        op: &Symbol,
+        lower: Option<&AST>,
+        upper: Option<&AST>,
+        body: &AST,
+    ) -> Self::Output;
+
+    /// Formats a limit with its variable, the value the variable approaches, the direction it
+    /// approaches from if the limit is one-sided, and the body.
+    fn format_limit(
+        &mut self,
+        var: &AST,
+        target: &AST,
+        direction: Option<Direction>,
+        body: &AST,
+    ) -> Self::Output;
+
+    /// Formats a derivative of some kind and order, of the given expression with respect to the
+    /// given variable.
+    fn format_derivative(
+        &mut self,
+        kind: DerivativeKind,
+        order: u32,
+        function: &AST,
+        var: &AST,
+    ) -> Self::Output;
+
+    /// Formats an expression with some number of prime marks.
+    fn format_prime(&mut self, arg: &AST, count: u32) -> Self::Output;
+
+    /// Formats a chain of relations, given the first operand and then each relation with the
+    /// operand after it.
+    fn format_chain(&mut self, first: &AST, rest: &[(Op, AST)]) -> Self::Output;
+
+    /// Formats a quantifier like ∀, with the variable it binds and the statement it applies to.
+    fn format_quantifier(&mut self, quantifier: &Symbol, binding: &AST, body: &AST)
+        -> Self::Output;
+
+    /// Formats a set written as a list of its elements.
+    fn format_set(&mut self, elements: &[AST]) -> Self::Output;
+
+    /// Formats a set in set-builder notation, with its members and the condition they satisfy.
+    fn format_set_builder(&mut self, members: &AST, condition: &AST) -> Self::Output;
+
+    /// Formats an expression between a pair of delimiters of the given kind, like |x|.
+    fn format_fenced(&mut self, kind: DelimKind, inner: &AST) -> Self::Output;
+
+    /// Formats an expression grouped by the delimiters written around it, like (a + b).
+    fn format_group(&mut self, kind: DelimKind, inner: &AST) -> Self::Output;
+
+    /// Formats a matrix, given as its rows.
+    fn format_matrix(&mut self, rows: &[Vec<AST>]) -> Self::Output;
+
+    /// Formats a piecewise function, given as each value with its condition, if it has one.
+    fn format_piecewise(&mut self, cases: &[(AST, Option<AST>)]) -> Self::Output;
 
     /// Formats an `AST` into the specified output type.
     fn format(&mut self, ast: &AST) -> Self::Output {
+        // parentheses are only added where they're needed, so anything else has to go first, but
+        // the parentheses of f(x) are what make it an application
+        if self.grouping() == Grouping::Minimal && ast.has_plain_groups() {
+            return self.format(&ast.without_plain_groups());
+        }
         match ast {
             AST::Sym(sym) => self.format_symbol(sym),
-            AST::Number(string) => self.format_number(string),
+            AST::Number(num) => self.format_number(num),
+            AST::MixedNumber(whole, numerator, denominator) => {
+                self.format_mixed_number(whole.as_deref(), numerator, denominator)
+            }
             AST::BinaryExpr(op, arg1, arg2) => self.format_binary_expr(op, arg1, arg2),
             AST::UnaryExpr(op, arg) => self.format_unary_expr(op, arg),
             AST::Function(name, args) => self.format_function(name, args),
+            AST::BigOp(op, lower, upper, body) => {
+                self.format_big_op(op, lower.as_deref(), upper.as_deref(), body)
+            }
+            AST::Limit(var, target, direction, body) => {
+                self.format_limit(var, target, *direction, body)
+            }
+            AST::Derivative(kind, order, function, var) => {
+                self.format_derivative(*kind, *order, function, var)
+            }
+            AST::Prime(arg, count) => self.format_prime(arg, *count),
+            AST::Chain(first, rest) => self.format_chain(first, rest),
+            AST::Quantifier(quantifier, binding, body) => {
+                self.format_quantifier(quantifier, binding, body)
+            }
+            AST::Set(elements) => self.format_set(elements),
+            AST::SetBuilder(members, condition) => self.format_set_builder(members, condition),
+            AST::Fenced(kind, inner) => self.format_fenced(*kind, inner),
+            AST::Group(kind, inner) => self.format_group(*kind, inner),
+            AST::Matrix(rows) => self.format_matrix(rows),
+            AST::Piecewise(cases) => self.format_piecewise(cases),
         }
     }
 }
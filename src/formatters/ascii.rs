@@ -0,0 +1,534 @@
+//! A Formatter for plain ASCII output.
+
+use crate::{
+    ast::{self, SymbolBinaryOp, AST},
+    delimiter::{self, DelimKind},
+    formatter::Grouping,
+    formatters::precedence::{
+        big_op_needs_parens, chain_needs_parens, derivative_needs_parens, leibniz_form,
+        need_parens, postfix_needs_parens, quantifier_needs_parens,
+    },
+    operators::Op,
+    symbols,
+};
+
+/// A formatter for plain ASCII, for places like terminals that can't handle anything else. The
+/// output is meant to be read back in: parsing it with `AsciiParser` gives back the same tree, except
+/// that any parentheses that had to be added come back as groups. To make that work, juxtaposed
+/// arguments are separated by a space (`a b`, not `ab`), and only the parentheses that are needed
+/// are added.
+#[derive(Default)]
+pub struct AsciiFormatter {
+    /// How to deal with the groups written in the input, like the parentheses in (a + b) c.
+    pub grouping: Grouping,
+}
+
+impl crate::formatter::Formatter for AsciiFormatter {
+    type Output = String;
+
+    fn grouping(&self) -> Grouping {
+        self.grouping
+    }
+
+    fn format_symbol(&mut self, sym: &ast::Symbol) -> Self::Output {
+        // some symbols have extra whitespace in their representations to avoid matching inside
+        // words, which isn't needed for output
+        sym.ascii_repr.trim().to_string()
+    }
+
+    fn format_number(&mut self, num: &ast::Number) -> Self::Output {
+        let mut output = num.mantissa.clone();
+        if let Some(repeating) = &num.repeating {
+            output.push_str(&format!("({})", repeating));
+        }
+        if let Some(exponent) = &num.exponent {
+            output.push_str(&format!("e{}", exponent));
+        }
+        output
+    }
+
+    fn format_mixed_number(
+        &mut self,
+        whole: Option<&str>,
+        numerator: &str,
+        denominator: &str,
+    ) -> Self::Output {
+        match whole {
+            Some(whole) => format!("{} {}/{}", whole, numerator, denominator),
+            // 1/2 on its own would be read back as a fraction, not a number
+            None => symbols::unicode_fraction(numerator, denominator),
+        }
+    }
+
+    fn format_binary_expr(&mut self, op: &ast::BinaryOp, arg1: &AST, arg2: &AST) -> Self::Output {
+        let (left_p, right_p) = need_parens(op, arg1, arg2);
+        let left_no_paren = self.format(arg1);
+        let left = if left_p {
+            format!("({})", left_no_paren)
+        } else {
+            left_no_paren.clone()
+        };
+        let right_no_paren = self.format(arg2);
+        let right = if right_p {
+            format!("({})", right_no_paren)
+        } else {
+            right_no_paren.clone()
+        };
+        match op {
+            ast::BinaryOp::Generic(SymbolBinaryOp { op, fixity }) => {
+                let symbol = self.format_symbol(&op.sym);
+                match fixity {
+                    ast::Fixity::Prefix => format!("{} {} {}", symbol, left, right),
+                    ast::Fixity::Infix => format!("{} {} {}", left, symbol, right),
+                    ast::Fixity::Postfix => format!("{} {} {}", left, right, symbol),
+                }
+            }
+            ast::BinaryOp::Power => format!("{}^{}", left, right),
+            ast::BinaryOp::Subscript => format!("{}_{}", left, right),
+            ast::BinaryOp::Frac => format!("{} / {}", left, right),
+            // the base has to be a single token to read back in, so any expression gets parentheses
+            ast::BinaryOp::Log => match arg1 {
+                AST::BinaryExpr(_, _, _) => format!("log_({})({})", left_no_paren, right_no_paren),
+                _ => format!("log_{}({})", left, right_no_paren),
+            },
+            // a space keeps juxtaposed symbols from running together into one name
+            ast::BinaryOp::Root => match left_no_paren.as_str() {
+                "2" => format!("sqrt({})", right_no_paren),
+                "3" => format!("cbrt({})", right_no_paren),
+                index => format!("root({}, {})", index, right_no_paren),
+            },
+            // an application is written like a function: f(x), not f (x)
+            ast::BinaryOp::Concat if ast::is_application(arg1, arg2) => {
+                format!("{}{}", left, right)
+            }
+            ast::BinaryOp::Concat => format!("{} {}", left, right),
+        }
+    }
+
+    fn format_unary_expr(&mut self, op: &ast::UnaryOp, arg: &AST) -> Self::Output {
+        let arg_no_paren = self.format(arg);
+        match op {
+            ast::UnaryOp::Generic(sym) => {
+                let arg = match arg {
+                    AST::BinaryExpr(_, _, _) => format!("({})", arg_no_paren),
+                    _ => arg_no_paren,
+                };
+                let sym = self.format_symbol(sym);
+                // a word needs a space to keep it apart from the argument: not p, not notp
+                if sym.ends_with(char::is_alphabetic) {
+                    format!("{} {}", sym, arg)
+                } else {
+                    format!("{}{}", sym, arg)
+                }
+            }
+            ast::UnaryOp::Postfix(sym) => {
+                let arg = if postfix_needs_parens(sym, arg) {
+                    format!("({})", arg_no_paren)
+                } else {
+                    arg_no_paren
+                };
+                let sym = self.format_symbol(sym);
+                // a word needs a space to keep it apart from the argument: x deg, not xdeg
+                if sym.starts_with(char::is_alphabetic) {
+                    format!("{} {}", arg, sym)
+                } else {
+                    format!("{}{}", arg, sym)
+                }
+            }
+        }
+    }
+
+    fn format_function(&mut self, name: &ast::Symbol, args: &[AST]) -> Self::Output {
+        let name = self.format_symbol(name);
+        let args: Vec<String> = args.iter().map(|ast| self.format(ast)).collect();
+        format!("{}({})", name, args.join(", "))
+    }
+
+    fn format_big_op(
+        &mut self,
+        op: &ast::Symbol,
+        lower: Option<&AST>,
+        upper: Option<&AST>,
+        body: &AST,
+    ) -> Self::Output {
+        let mut scripted = self.format_symbol(op);
+        // a bound has to be a single token to read back in
+        let mut bound = |bound: &AST| match bound {
+            AST::Sym(sym) if sym.ascii_repr.chars().all(char::is_alphanumeric) => {
+                self.format(bound)
+            }
+            AST::Number(_) => self.format(bound),
+            _ => format!("({})", self.format(bound)),
+        };
+        if let Some(lower) = lower {
+            scripted = format!("{}_{}", scripted, bound(lower));
+        }
+        if let Some(upper) = upper {
+            scripted = format!("{}^{}", scripted, bound(upper));
+        }
+        let body_no_paren = self.format(body);
+        if big_op_needs_parens(body) {
+            format!("{} ({})", scripted, body_no_paren)
+        } else {
+            format!("{} {}", scripted, body_no_paren)
+        }
+    }
+
+    fn format_limit(
+        &mut self,
+        var: &AST,
+        target: &AST,
+        direction: Option<ast::Direction>,
+        body: &AST,
+    ) -> Self::Output {
+        let direction = match direction {
+            Some(ast::Direction::Above) => "^+",
+            Some(ast::Direction::Below) => "^-",
+            None => "",
+        };
+        let body_no_paren = self.format(body);
+        let body = if big_op_needs_parens(body) {
+            format!("({})", body_no_paren)
+        } else {
+            body_no_paren
+        };
+        format!(
+            "{}_({} {} {}{}) {}",
+            self.format_symbol(&symbols::LIM),
+            self.format(var),
+            self.format_symbol(&symbols::TO),
+            self.format(target),
+            direction,
+            body
+        )
+    }
+
+    fn format_derivative(
+        &mut self,
+        kind: ast::DerivativeKind,
+        order: u32,
+        function: &AST,
+        var: &AST,
+    ) -> Self::Output {
+        // partial is a whole word, so it gets spaces around it to keep it apart from the rest
+        let (d, sep) = match kind {
+            ast::DerivativeKind::Ordinary => ("d".to_string(), ""),
+            ast::DerivativeKind::Partial => (self.format_symbol(&symbols::PARTIAL), " "),
+        };
+        let (d_n, var) = if order == 1 {
+            (d.clone(), self.format(var))
+        } else {
+            (
+                format!("{}^{}", d, order),
+                format!("{}^{}", self.format(var), order),
+            )
+        };
+        if leibniz_form(function) {
+            format!(
+                "{}{}{}{}/{}{}{}{}",
+                d_n,
+                sep,
+                self.format(function),
+                sep,
+                sep,
+                d,
+                sep,
+                var
+            )
+        } else {
+            let body_no_paren = self.format(function);
+            let body = if derivative_needs_parens(function) {
+                format!("({})", body_no_paren)
+            } else {
+                body_no_paren
+            };
+            format!("{}{}/{}{}{}{} {}", d_n, sep, sep, d, sep, var, body)
+        }
+    }
+
+    fn format_prime(&mut self, arg: &AST, count: u32) -> Self::Output {
+        let arg_no_paren = self.format(arg);
+        let arg = if postfix_needs_parens(&symbols::PRIME, arg) {
+            format!("({})", arg_no_paren)
+        } else {
+            arg_no_paren
+        };
+        format!(
+            "{}{}",
+            arg,
+            self.format_symbol(&symbols::PRIME).repeat(count as usize)
+        )
+    }
+    fn format_chain(&mut self, first: &AST, rest: &[(Op, AST)]) -> Self::Output {
+        let parens = chain_needs_parens(first, rest);
+        let mut operands = std::iter::once(first)
+            .chain(rest.iter().map(|(_, operand)| operand))
+            .zip(parens)
+            .map(|(operand, parens)| {
+                let operand = self.format(operand);
+                if parens {
+                    format!("({})", operand)
+                } else {
+                    operand
+                }
+            })
+            .collect::<Vec<String>>()
+            .into_iter();
+        let mut output = operands.next().unwrap();
+        for (op, _) in rest {
+            output = format!(
+                "{} {} {}",
+                output,
+                self.format_symbol(&op.sym),
+                operands.next().unwrap()
+            );
+        }
+        output
+    }
+
+    fn format_quantifier(
+        &mut self,
+        quantifier: &ast::Symbol,
+        binding: &AST,
+        body: &AST,
+    ) -> Self::Output {
+        let quantifier = self.format_symbol(quantifier);
+        let binding = self.format(binding);
+        let body_no_paren = self.format(body);
+        if quantifier_needs_parens(body) {
+            format!("{} {}, ({})", quantifier, binding, body_no_paren)
+        } else {
+            format!("{} {}, {}", quantifier, binding, body_no_paren)
+        }
+    }
+
+    fn format_set(&mut self, elements: &[AST]) -> Self::Output {
+        let elements: Vec<String> = elements.iter().map(|ast| self.format(ast)).collect();
+        format!("{{{}}}", elements.join(", "))
+    }
+
+    fn format_set_builder(&mut self, members: &AST, condition: &AST) -> Self::Output {
+        format!("{{{} | {}}}", self.format(members), self.format(condition))
+    }
+
+    fn format_fenced(&mut self, kind: DelimKind, inner: &AST) -> Self::Output {
+        let (left, right) = delimiter::pair(kind);
+        // a list inside, like the a, b in ⟨a, b⟩, is written like a function's arguments
+        let inner: Vec<String> = inner
+            .comma_list()
+            .into_iter()
+            .map(|elem| self.format(elem))
+            .collect();
+        let inner = inner.join(", ");
+        // bars right next to each other would read as a norm: | |x| - 1 |, not ||x| - 1|
+        let pad = if inner.starts_with(['|', '‖']) || inner.ends_with(['|', '‖']) {
+            " "
+        } else {
+            ""
+        };
+        format!(
+            "{}{}{}{}{}",
+            self.format_symbol(&left),
+            pad,
+            inner,
+            pad,
+            self.format_symbol(&right)
+        )
+    }
+
+    fn format_group(&mut self, kind: DelimKind, inner: &AST) -> Self::Output {
+        let (left, right) = delimiter::pair(kind);
+        let inner: Vec<String> = inner
+            .comma_list()
+            .into_iter()
+            .map(|elem| self.format(elem))
+            .collect();
+        format!(
+            "{}{}{}",
+            self.format_symbol(&left),
+            inner.join(", "),
+            self.format_symbol(&right)
+        )
+    }
+
+    fn format_matrix(&mut self, rows: &[Vec<AST>]) -> Self::Output {
+        // each row in its own brackets, as in [[1, 2], [3, 4]]
+        let mut formatted = vec![];
+        for row in rows {
+            let row: Vec<String> = row.iter().map(|elem| self.format(elem)).collect();
+            formatted.push(format!("[{}]", row.join(", ")));
+        }
+        format!("[{}]", formatted.join(", "))
+    }
+
+    fn format_piecewise(&mut self, cases: &[(AST, Option<AST>)]) -> Self::Output {
+        // written the way it's parsed, as in {x if x >= 0; -x otherwise}
+        let mut formatted = vec![];
+        for (value, condition) in cases {
+            let value = self.format(value);
+            formatted.push(match condition {
+                Some(condition) => format!("{} if {}", value, self.format(condition)),
+                None => format!("{} otherwise", value),
+            });
+        }
+        format!("{{{}}}", formatted.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter::Formatter;
+    use crate::formatters::{latex::LatexFormatter, unicode::UnicodeFormatter};
+    use crate::parsers::{
+        ascii::ImplicitMultiplication, ASTParser, AsciiParser, LatexParser, UnicodeParser,
+    };
+
+    #[test]
+    fn test_normalize() {
+        let tree = UnicodeParser::default()
+            .parse(&"α · x² ± √(y + 1)")
+            .unwrap();
+        assert_eq!(
+            AsciiFormatter::default().format(&tree),
+            "alpha * x^2 +/- sqrt(y + 1)"
+        );
+        let tree = LatexParser::default()
+            .parse(&r"\frac{a - b}{2} \times \cos^{2} \theta")
+            .unwrap();
+        assert_eq!(
+            AsciiFormatter::default().format(&tree),
+            "(a - b) / 2 * cos^2(theta)"
+        );
+    }
+
+    #[test]
+    fn test_grouping() {
+        let tree = AsciiParser::default()
+            .parse(&"[a + b] * c - ((x)) + (y^2)!")
+            .unwrap();
+        // only the parentheses that are needed, unless groups are kept on purpose
+        assert_eq!(
+            AsciiFormatter::default().format(&tree),
+            "(a + b) * c - x + (y^2)!"
+        );
+        let mut preserve = AsciiFormatter {
+            grouping: Grouping::Preserve,
+        };
+        assert_eq!(preserve.format(&tree), "[a + b] * c - ((x)) + (y^2)!");
+    }
+
+    #[test]
+    fn test_applications() {
+        let tree = AsciiParser::default()
+            .parse(&"f(x) + g_1(x, y) - f^-1((x))")
+            .unwrap();
+        assert_eq!(
+            AsciiFormatter::default().format(&tree),
+            "f(x) + g_1(x, y) - f^-1(x)"
+        );
+        let mut preserve = AsciiFormatter {
+            grouping: Grouping::Preserve,
+        };
+        assert_eq!(preserve.format(&tree), "f(x) + g_1(x, y) - f^-1((x))");
+    }
+
+    #[test]
+    fn test_implicit_multiplication() {
+        let calculator = AsciiParser::default();
+        let physicist = AsciiParser {
+            implicit_multiplication: ImplicitMultiplication::TighterThanDivision,
+            ..AsciiParser::default()
+        };
+        let mut ascii = AsciiFormatter::default();
+        let mut latex = LatexFormatter::default();
+        let mut unicode = UnicodeFormatter::default();
+        for parser in [&calculator, &physicist] {
+            let tree = parser.parse(&"2x^2").unwrap();
+            assert_eq!(ascii.format(&tree), "2 x^2");
+            assert_eq!(latex.format(&tree), "2x^{2}");
+            assert_eq!(unicode.format(&tree), "2x^2");
+            let tree = parser.parse(&"x^2 y").unwrap();
+            assert_eq!(ascii.format(&tree), "x^2 y");
+            assert_eq!(latex.format(&tree), "x^{2}y");
+            assert_eq!(unicode.format(&tree), "x^2y");
+        }
+        // the output reads the same way under either setting
+        let tree = calculator.parse(&"1/2 x").unwrap();
+        assert_eq!(ascii.format(&tree), "(1 / 2) x");
+        assert_eq!(latex.format(&tree), r"\frac{ 1 }{ 2 }x");
+        assert_eq!(
+            LatexParser::default().parse(&latex.format(&tree)).unwrap(),
+            tree
+        );
+        for parser in [&calculator, &physicist] {
+            assert_eq!(
+                parser.parse(&ascii.format(&tree)).unwrap().without_groups(),
+                tree
+            );
+        }
+        let tree = physicist.parse(&"1/2 x").unwrap();
+        assert_eq!(ascii.format(&tree), "1 / (2 x)");
+        assert_eq!(latex.format(&tree), r"\frac{ 1 }{ 2x }");
+        for parser in [&calculator, &physicist] {
+            assert_eq!(
+                parser.parse(&ascii.format(&tree)).unwrap().without_groups(),
+                tree
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let parser = AsciiParser::default();
+        for input in [
+            "2 / (sin mu + 1)",
+            "mu ^ (3 * (4 + 5))",
+            "cos^2(A) + sin^2(B)",
+            "2 / arccos mu + 1",
+            "(a + b) * c ^ (2 - x)",
+            "-x + max(1, 2, -(y - 1))",
+            "a b c + 2 pi",
+            "1 +/- 2 ^ -3 / 4",
+            "alpha (beta - gamma) Omega",
+            "sqrt(2) cbrt x",
+            "root(n + 1, x) - root4(y) / sqrt(x^2 + 1)",
+            "sum_(i=1)^n i^2 + prod_k (k + 1) x",
+            "int_0^1 f(x) dx - int^oo e^-t dt",
+            "lim_(x->0) sin(x) / x + lim_(n -> oo^-) (1 + 1/n)^n",
+            "x_i^2 + a_(i + 1) y_1",
+            "log_2 x + log_(b + 1)(x_i)",
+            "d^2y/dx^2 + 2 dy/dx - d/dx (x^2 + 1)",
+            "partial f / partial x + f''(x) - (x^2)'",
+            "(n + 1)! / n!! + 2 x deg - 50% + (A B)^T",
+            "x + 1 <= 2 y",
+            "(a - b)^2 != a^2 - b^2",
+            "max(a = b, c ~ d) > -1",
+            "0 < x + 1 <= 2 y != z",
+            "not (a and b) or c xor d => e <=> (not f) = g",
+            "forall x > 0, exists y, x < y and not y = 2 x",
+            "{x in RR | x notin A union B cap C} subsetneq {1, 2} \\ {}",
+            "2|x - 1| + ||v|| |y| - | |z| - 1 |",
+            "floor((x + 1) / 2) ceil(y) - <<a, b>>^2",
+            "[[1, x^2], [-y, 4]] [a; b] + [[2]]",
+            "f(x) = {x^2 if x >= 0; -x, if -1 < x < 0; 1 otherwise}",
+            "1.2 * 10^-3 + 6.02E23 (1e5)^2 - 12.5 / .5",
+            "-1 1/2 + x (2 3/4)^2 - 0.(3) / 0.1666...",
+            "½ + 2 ½ - ⁵⁄₇ x",
+            "0.3... + 0.333…",
+        ] {
+            let tree = parser.parse(&input).unwrap();
+            let ascii = AsciiFormatter::default().format(&tree);
+            // parentheses that had to be added come back as groups, but nothing else changes
+            let reparsed = parser.parse(&ascii).unwrap();
+            assert_eq!(
+                reparsed.without_groups(),
+                tree.without_groups(),
+                "{} -> {}",
+                input,
+                ascii
+            );
+            assert_eq!(AsciiFormatter::default().format(&reparsed), ascii);
+        }
+    }
+}
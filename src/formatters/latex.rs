@@ -2,43 +2,85 @@
 
 use crate::{
     ast::{self, SymbolBinaryOp},
-    formatters::precedence::need_parens,
+    delimiter::{self, DelimKind},
+    formatter::{Grouping, NumberStyle},
+    formatters::precedence::{
+        big_op_needs_parens, chain_needs_parens, derivative_needs_parens, leibniz_form,
+        need_parens, postfix_needs_parens, quantifier_needs_parens, unary_needs_parens,
+    },
     operators::Op,
+    symbols,
 };
 
 /// A formatter for LaTeX.
 #[derive(Default)]
-pub struct LatexFormatter {}
+pub struct LatexFormatter {
+    /// How to deal with the groups written in the input, like the parentheses in (a + b) c.
+    pub grouping: Grouping,
+    /// How to write the digits of numbers.
+    pub numbers: NumberStyle,
+}
 
 impl crate::formatter::Formatter for LatexFormatter {
     type Output = String;
 
+    fn grouping(&self) -> Grouping {
+        self.grouping
+    }
+
     fn format_symbol(&mut self, sym: &ast::Symbol) -> Self::Output {
         sym.latex_repr.clone()
     }
 
-    fn format_number(&mut self, dec: &str) -> Self::Output {
-        dec.to_string()
+    fn format_number(&mut self, num: &ast::Number) -> Self::Output {
+        // a bare comma gets space after it in math mode, so it goes in braces
+        let mut output = self.numbers.format_mantissa(&num.mantissa, "{,}", r"\,");
+        if let Some(repeating) = &num.repeating {
+            output.push_str(&format!(r"\overline{{{}}}", repeating));
+        }
+        if let Some(exponent) = &num.exponent {
+            output.push_str(&format!(r" \times 10^{{{}}}", exponent));
+        }
+        output
+    }
+
+    fn format_mixed_number(
+        &mut self,
+        whole: Option<&str>,
+        numerator: &str,
+        denominator: &str,
+    ) -> Self::Output {
+        format!(
+            r"{}\tfrac{{{}}}{{{}}}",
+            whole.unwrap_or_default(),
+            numerator,
+            denominator
+        )
     }
 
     fn format_binary_expr(
         &mut self,
         op: &ast::BinaryOp,
-        arg1: &Box<ast::AST>,
-        arg2: &Box<ast::AST>,
+        arg1: &ast::AST,
+        arg2: &ast::AST,
     ) -> Self::Output {
         let (left_p, right_p) = need_parens(op, arg1, arg2);
-        let left_no_paren = self.format(&arg1.to_owned());
+        // \frac sets itself apart, so \frac{1}{2}x needs no parentheses (but 2\frac{1}{2} would
+        // look like a mixed number without them)
+        let left_p = left_p
+            && !(op == &ast::BinaryOp::Concat
+                && matches!(arg1, ast::AST::BinaryExpr(ast::BinaryOp::Frac, _, _)));
+        let left_no_paren = self.format(arg1);
         let left = if left_p {
             format!("({})", left_no_paren)
         } else {
-            format!("{}", left_no_paren)
+            left_no_paren.clone()
         };
-        let right_no_paren = self.format(&arg2.to_owned());
+        let right_no_paren = self.format(arg2);
         let right = if right_p {
             format!("({})", right_no_paren)
         } else {
-            format!("{}", right_no_paren)
+            right_no_paren.clone()
         };
         match op {
             ast::BinaryOp::Generic(SymbolBinaryOp { op, fixity }) => {
@@ -51,38 +93,307 @@ impl crate::formatter::Formatter for LatexFormatter {
             }
             // superscript takes care of parenthesis need
             ast::BinaryOp::Power => format!("{}^{{{}}}", left, right_no_paren),
+            // so does subscript, and x_{i}^{2} comes out naturally as the power of x_{i}
+            ast::BinaryOp::Subscript => format!("{}_{{{}}}", left, right_no_paren),
             // fractions never need parentheses for their outer arguments
             ast::BinaryOp::Frac => format!("\\frac{{ {} }}{{ {} }}", left_no_paren, right_no_paren),
             // log subscript means no paren is needed
             ast::BinaryOp::Log => {
                 format!("\\log_{{ {} }} \\left( {} \\right)", left_no_paren, right)
             }
+            ast::BinaryOp::Root => match left_no_paren.as_str() {
+                "2" => format!("\\sqrt{{{}}}", right_no_paren),
+                index => format!("\\sqrt[{}]{{{}}}", index, right_no_paren),
+            },
+            // a command right before a letter would run into it: \rfloor x, not \rfloorx
+            ast::BinaryOp::Concat
+                if ends_in_command(&left) && right.starts_with(char::is_alphabetic) =>
+            {
+                format!(r"{} {}", left, right)
+            }
+            // digits right next to each other would read as one number: 2 \cdot 3, not 23, and
+            // 1.2 \cdot .3, not 1.2.3
+            ast::BinaryOp::Concat
+                if left.ends_with(|c: char| c.is_ascii_digit())
+                    && right.starts_with(|c: char| c.is_ascii_digit() || c == '.') =>
+            {
+                format!(r"{} \cdot {}", left, right)
+            }
             ast::BinaryOp::Concat => format!(r"{}{}", left, right),
         }
     }
 
-    fn format_unary_expr(&mut self, op: &ast::UnaryOp, arg: &Box<ast::AST>) -> Self::Output {
-        let arg = self.format(&arg.to_owned());
+    fn format_unary_expr(&mut self, op: &ast::UnaryOp, arg: &ast::AST) -> Self::Output {
+        let arg_no_paren = self.format(arg);
         match op {
             ast::UnaryOp::Generic(sym) => {
+                let arg = if unary_needs_parens(sym, arg) {
+                    format!("({})", arg_no_paren)
+                } else {
+                    arg_no_paren
+                };
                 let sym = self.format_symbol(sym);
                 format!("{} {}", sym, arg)
             }
+            ast::UnaryOp::Postfix(sym) => {
+                let arg = if postfix_needs_parens(sym, arg) {
+                    format!("({})", arg_no_paren)
+                } else {
+                    arg_no_paren
+                };
+                format!("{}{}", arg, self.format_symbol(sym))
+            }
         }
     }
 
-    fn format_function(&mut self, name: &ast::Symbol, args: &Vec<ast::AST>) -> Self::Output {
+    fn format_function(&mut self, name: &ast::Symbol, args: &[ast::AST]) -> Self::Output {
         let name = self.format_symbol(name);
         let args: Vec<String> = args.iter().map(|ast| self.format(ast)).collect();
         format!("{}\\left({}\\right)", name, args.join(", "))
     }
+
+    fn format_big_op(
+        &mut self,
+        op: &ast::Symbol,
+        lower: Option<&ast::AST>,
+        upper: Option<&ast::AST>,
+        body: &ast::AST,
+    ) -> Self::Output {
+        let mut scripted = self.format_symbol(op);
+        if let Some(lower) = lower {
+            scripted = format!("{}_{{{}}}", scripted, self.format(lower));
+        }
+        if let Some(upper) = upper {
+            scripted = format!("{}^{{{}}}", scripted, self.format(upper));
+        }
+        let body = match body.split_differential() {
+            // integrals get a thin space before the differential, as in \int f(x) \, dx
+            Some((integrand, d)) if op == &*symbols::INT => {
+                let integrand = if big_op_needs_parens(&integrand) {
+                    format!("({})", self.format(&integrand))
+                } else {
+                    self.format(&integrand)
+                };
+                format!("{}\\,{}", integrand, self.format_symbol(&d))
+            }
+            _ if big_op_needs_parens(body) => format!("({})", self.format(body)),
+            _ => self.format(body),
+        };
+        format!("{} {}", scripted, body)
+    }
+
+    fn format_limit(
+        &mut self,
+        var: &ast::AST,
+        target: &ast::AST,
+        direction: Option<ast::Direction>,
+        body: &ast::AST,
+    ) -> Self::Output {
+        let direction = match direction {
+            Some(ast::Direction::Above) => "^{+}",
+            Some(ast::Direction::Below) => "^{-}",
+            None => "",
+        };
+        let body_no_paren = self.format(body);
+        let body = if big_op_needs_parens(body) {
+            format!("({})", body_no_paren)
+        } else {
+            body_no_paren
+        };
+        format!(
+            "{}_{{{} {} {}{}}} {}",
+            self.format_symbol(&symbols::LIM),
+            self.format(var),
+            self.format_symbol(&symbols::TO),
+            self.format(target),
+            direction,
+            body
+        )
+    }
+
+    fn format_derivative(
+        &mut self,
+        kind: ast::DerivativeKind,
+        order: u32,
+        function: &ast::AST,
+        var: &ast::AST,
+    ) -> Self::Output {
+        // \partial is a command, so it needs a space before what comes after it
+        let (d, sep) = match kind {
+            ast::DerivativeKind::Ordinary => ("d".to_string(), ""),
+            ast::DerivativeKind::Partial => (self.format_symbol(&symbols::PARTIAL), " "),
+        };
+        let (d_n, var) = if order == 1 {
+            (d.clone(), self.format(var))
+        } else {
+            (
+                format!("{}^{{{}}}", d, order),
+                format!("{}^{{{}}}", self.format(var), order),
+            )
+        };
+        if leibniz_form(function) {
+            format!(
+                "\\frac{{{}{}{}}}{{{}{}{}}}",
+                d_n,
+                sep,
+                self.format(function),
+                d,
+                sep,
+                var
+            )
+        } else {
+            let body_no_paren = self.format(function);
+            let body = if derivative_needs_parens(function) {
+                format!("({})", body_no_paren)
+            } else {
+                body_no_paren
+            };
+            format!("\\frac{{{}}}{{{}{}{}}} {}", d_n, d, sep, var, body)
+        }
+    }
+
+    fn format_prime(&mut self, arg: &ast::AST, count: u32) -> Self::Output {
+        let arg_no_paren = self.format(arg);
+        let arg = if postfix_needs_parens(&symbols::PRIME, arg) {
+            format!("({})", arg_no_paren)
+        } else {
+            arg_no_paren
+        };
+        format!("{}{}", arg, "'".repeat(count as usize))
+    }
+    fn format_chain(&mut self, first: &ast::AST, rest: &[(Op, ast::AST)]) -> Self::Output {
+        let parens = chain_needs_parens(first, rest);
+        let mut operands = std::iter::once(first)
+            .chain(rest.iter().map(|(_, operand)| operand))
+            .zip(parens)
+            .map(|(operand, parens)| {
+                let operand = self.format(operand);
+                if parens {
+                    format!("({})", operand)
+                } else {
+                    operand
+                }
+            })
+            .collect::<Vec<String>>()
+            .into_iter();
+        let mut output = operands.next().unwrap();
+        for (op, _) in rest {
+            output = format!(
+                "{} {} {}",
+                output,
+                self.format_symbol(&op.sym),
+                operands.next().unwrap()
+            );
+        }
+        output
+    }
+
+    fn format_quantifier(
+        &mut self,
+        quantifier: &ast::Symbol,
+        binding: &ast::AST,
+        body: &ast::AST,
+    ) -> Self::Output {
+        let quantifier = self.format_symbol(quantifier);
+        let binding = self.format(binding);
+        let body_no_paren = self.format(body);
+        if quantifier_needs_parens(body) {
+            format!("{} {}, ({})", quantifier, binding, body_no_paren)
+        } else {
+            format!("{} {}, {}", quantifier, binding, body_no_paren)
+        }
+    }
+
+    fn format_set(&mut self, elements: &[ast::AST]) -> Self::Output {
+        let elements: Vec<String> = elements.iter().map(|ast| self.format(ast)).collect();
+        if elements.is_empty() {
+            r"\{\}".to_string()
+        } else {
+            format!(r"\{{ {} \}}", elements.join(", "))
+        }
+    }
+
+    fn format_set_builder(&mut self, members: &ast::AST, condition: &ast::AST) -> Self::Output {
+        format!(
+            r"\{{ {} \mid {} \}}",
+            self.format(members),
+            self.format(condition)
+        )
+    }
+
+    fn format_fenced(&mut self, kind: DelimKind, inner: &ast::AST) -> Self::Output {
+        let (left, right) = delimiter::pair(kind);
+        // a list inside, like the a, b in ⟨a, b⟩, is written like a function's arguments
+        let inner: Vec<String> = inner
+            .comma_list()
+            .into_iter()
+            .map(|elem| self.format(elem))
+            .collect();
+        // parentheses are the arguments of an application, which are written as usual: f(x)
+        if kind == DelimKind::Paren {
+            return format!("({})", inner.join(", "));
+        }
+        format!(
+            "{} {} {}",
+            self.format_symbol(&left),
+            inner.join(", "),
+            self.format_symbol(&right)
+        )
+    }
+
+    fn format_group(&mut self, kind: DelimKind, inner: &ast::AST) -> Self::Output {
+        // the delimiters grow to fit what's inside, like the parentheses around a function's
+        // arguments
+        let (left, right) = delimiter::pair(kind);
+        let inner: Vec<String> = inner
+            .comma_list()
+            .into_iter()
+            .map(|elem| self.format(elem))
+            .collect();
+        format!(
+            "{}{}{}",
+            self.format_symbol(&left),
+            inner.join(", "),
+            self.format_symbol(&right)
+        )
+    }
+
+    fn format_matrix(&mut self, rows: &[Vec<ast::AST>]) -> Self::Output {
+        let mut formatted = vec![];
+        for row in rows {
+            let row: Vec<String> = row.iter().map(|elem| self.format(elem)).collect();
+            formatted.push(row.join(" & "));
+        }
+        format!(
+            r"\begin{{pmatrix}} {} \end{{pmatrix}}",
+            formatted.join(r" \\ ")
+        )
+    }
+
+    fn format_piecewise(&mut self, cases: &[(ast::AST, Option<ast::AST>)]) -> Self::Output {
+        let mut formatted = vec![];
+        for (value, condition) in cases {
+            let value = self.format(value);
+            formatted.push(match condition {
+                Some(condition) => format!(r"{} & \text{{if }} {}", value, self.format(condition)),
+                None => format!(r"{} & \text{{otherwise}}", value),
+            });
+        }
+        format!(r"\begin{{cases}} {} \end{{cases}}", formatted.join(r" \\ "))
+    }
+}
+
+/// Whether some LaTeX ends with a command made of letters, like `\pi`.
+fn ends_in_command(latex: &str) -> bool {
+    let letters = latex.trim_end_matches(char::is_alphabetic);
+    letters.len() < latex.len() && letters.ends_with('\\')
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::formatter::Formatter;
-    use crate::parsers::{ASTParser, AsciiParser};
+    use crate::parsers::{ASTParser, AsciiParser, LatexParser};
 
     #[test]
     fn test_formatting() {
@@ -91,17 +402,17 @@ mod tests {
             Box::new(ast::AST::Function(
                 ast::Symbol::from("f"),
                 vec![
-                    ast::AST::Number("100".to_string()),
+                    ast::AST::Number("100".into()),
                     ast::AST::Sym(ast::Symbol::from("x")),
                 ],
             )),
             Box::new(ast::AST::UnaryExpr(
                 ast::UnaryOp::Generic(ast::Symbol::from("-")),
-                Box::new(ast::AST::Number("12.34".to_string())),
+                Box::new(ast::AST::Number("12.34".into())),
             )),
         );
         assert_eq!(
-            LatexFormatter {}.format(&tree),
+            LatexFormatter::default().format(&tree),
             r"\frac{ f\left(100, x\right) }{ - 12.34 }".to_string()
         );
     }
@@ -130,4 +441,261 @@ mod tests {
             r"\frac{ 2 }{ \arccos\left(\mu\right) } + 1".to_string()
         );
     }
+
+    #[test]
+    fn test_roots() {
+        let parser = AsciiParser::default();
+        let tree = parser.parse(&"sqrt(x + 1) / root(3, y)").unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\frac{ \sqrt{x + 1} }{ \sqrt[3]{y} }"
+        );
+    }
+
+    #[test]
+    fn test_big_ops() {
+        let parser = AsciiParser::default();
+        let tree = parser.parse(&"sum_(i=1)^n i^2").unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\sum_{i = 1}^{n} i^{2}"
+        );
+        let tree = parser.parse(&"int_0^1 sin(x) dx").unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\int_{0}^{1} \sin\left(x\right)\,dx"
+        );
+        let tree = parser.parse(&"prod_(k=1)^oo (1 + x_k) / 2").unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\prod_{k = 1}^{\infty} \frac{ 1 + x_{k} }{ 2 }"
+        );
+    }
+
+    #[test]
+    fn test_derivatives() {
+        let parser = AsciiParser::default();
+        let tree = parser
+            .parse(&"d^2y/dx^2 + partial f / partial x - d/dx (x^2)")
+            .unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\frac{d^{2}y}{dx^{2}} + \frac{\partial f}{\partial x} - \frac{d}{dx} (x^{2})"
+        );
+        // a primed function keeps its argument's parentheses even when groups aren't kept
+        let tree = parser.parse(&"f''(x) + (x + 1)'").unwrap();
+        assert_eq!(LatexFormatter::default().format(&tree), "f''(x) + (x + 1)'");
+        let mut minimal = LatexFormatter {
+            grouping: Grouping::Minimal,
+            ..Default::default()
+        };
+        assert_eq!(minimal.format(&tree), "f''(x) + (x + 1)'");
+        assert_eq!(
+            parser
+                .parse(&minimal.format(&tree))
+                .unwrap()
+                .without_groups(),
+            tree.without_groups()
+        );
+    }
+
+    #[test]
+    fn test_postfix() {
+        let tree = AsciiParser::default()
+            .parse(&"n! + (n + 1)!! - 90° + A^T 5%")
+            .unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"n! + (n + 1)!! - 90^{\circ} + A^{\top}5\%"
+        );
+    }
+
+    #[test]
+    fn test_relations() {
+        let tree = AsciiParser::default().parse(&"x + 1 <= 2 y").unwrap();
+        assert_eq!(LatexFormatter::default().format(&tree), r"x + 1 \le 2y");
+        let tree = AsciiParser::default().parse(&"a ~ b != c").unwrap();
+        assert_eq!(LatexFormatter::default().format(&tree), r"a \sim b \neq c");
+    }
+
+    #[test]
+    fn test_chains() {
+        let tree = AsciiParser::default().parse(&"0 < x^2 <= 1").unwrap();
+        assert_eq!(LatexFormatter::default().format(&tree), r"0 < x^{2} \le 1");
+    }
+
+    #[test]
+    fn test_logic() {
+        let tree = AsciiParser::default()
+            .parse(&"not (p and q) <=> !p || not q")
+            .unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\neg (p \land q) \iff \neg p \lor \neg q"
+        );
+        let tree = AsciiParser::default()
+            .parse(&"forall x, exists y, x < y => x != y")
+            .unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\forall x, \exists y, x < y \implies x \neq y"
+        );
+    }
+
+    #[test]
+    fn test_sets() {
+        let tree = AsciiParser::default()
+            .parse(&"{x in RR | x > 0} subset A union B cap C \\ {1, 2}")
+            .unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\{ x \in \mathbb{R} \mid x > 0 \} \subseteq A \cup B \cap C \setminus \{ 1, 2 \}"
+        );
+    }
+
+    #[test]
+    fn test_bars() {
+        let tree = AsciiParser::default().parse(&"|x - 1| + ||v||").unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\left| x - 1 \right| + \lVert v \rVert"
+        );
+    }
+
+    #[test]
+    fn test_delimiters() {
+        let tree = AsciiParser::default()
+            .parse(&"floor(x / 2) y + ceil(y) - <<a, b>>")
+            .unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\lfloor \frac{ x }{ 2 } \rfloor y + \lceil y \rceil - \langle a, b \rangle"
+        );
+    }
+
+    #[test]
+    fn test_grouping() {
+        let tree = AsciiParser::default()
+            .parse(&"[a + b] * c - ((x)) + [y^2]!")
+            .unwrap();
+        let mut preserve = LatexFormatter {
+            grouping: Grouping::Preserve,
+            ..Default::default()
+        };
+        assert_eq!(
+            preserve.format(&tree),
+            r"\left[a + b\right] \cdot c - \left(\left(x\right)\right) + \left[y^{2}\right]!"
+        );
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"(a + b) \cdot c - x + (y^{2})!"
+        );
+    }
+
+    #[test]
+    fn test_applications() {
+        let parser = AsciiParser::default();
+        let mut latex = LatexFormatter::default();
+        let tree = parser.parse(&"f(x) + g_1(x, y)").unwrap();
+        assert_eq!(latex.format(&tree), r"f(x) + g_{1}(x, y)");
+        let tree = parser.parse(&"int_0^1 f(x) dx").unwrap();
+        assert_eq!(latex.format(&tree), r"\int_{0}^{1} f(x)\,dx");
+        let tree = parser.parse(&"f(x) = {1 if x > 0; 0 otherwise}").unwrap();
+        assert_eq!(
+            latex.format(&tree),
+            r"f(x) = \begin{cases} 1 & \text{if } x > 0 \\ 0 & \text{otherwise} \end{cases}"
+        );
+    }
+
+    #[test]
+    fn test_matrices() {
+        let tree = AsciiParser::default()
+            .parse(&"[[1, 2], [3, 4]] [x; y]")
+            .unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\begin{pmatrix} 1 & 2 \\ 3 & 4 \end{pmatrix}\begin{pmatrix} x \\ y \end{pmatrix}"
+        );
+    }
+
+    #[test]
+    fn test_piecewise() {
+        let tree = AsciiParser::default()
+            .parse(&"{x^2 if x >= 0; 0 otherwise}")
+            .unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\begin{cases} x^{2} & \text{if } x \ge 0 \\ 0 & \text{otherwise} \end{cases}"
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        let tree = AsciiParser::default()
+            .parse(&"(1.2 * 10^-3)^2 - 12.5")
+            .unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"(1.2 \times 10^{-3})^{2} - 12.5"
+        );
+        // numbers next to each other are a product, not one number
+        let tree = AsciiParser::default().parse(&"2 3 x").unwrap();
+        assert_eq!(LatexFormatter::default().format(&tree), r"2 \cdot 3x");
+        let tree = AsciiParser::default().parse(&"1.2.3").unwrap();
+        assert_eq!(LatexFormatter::default().format(&tree), r"1.2 \cdot .3");
+    }
+
+    #[test]
+    fn test_mixed_numbers() {
+        // an improper fraction isn't part of the number before it
+        let tree = AsciiParser::default().parse(&"1 1/2 + ½ - 2 3/2").unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"1\tfrac{1}{2} + \tfrac{1}{2} - \frac{ 2 \cdot 3 }{ 2 }"
+        );
+    }
+
+    #[test]
+    fn test_repeating_decimals() {
+        let tree = AsciiParser::default()
+            .parse(&"0.1(6) + 0.1212... - 3.1...")
+            .unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"0.1\overline{6} + 0.\overline{12} - 3.1\ldots"
+        );
+        // digits cut off before an ellipsis stay cut off
+        let tree = AsciiParser::default().parse(&"0.3...").unwrap();
+        assert_eq!(LatexFormatter::default().format(&tree), r"0.3\ldots");
+        assert_eq!(LatexParser::default().parse(&r"0.3\ldots").unwrap(), tree);
+    }
+
+    #[test]
+    fn test_locale() {
+        let tree = AsciiParser::default()
+            .parse(&"1234567.891011 + 3.14 + 1000")
+            .unwrap();
+        let mut european = LatexFormatter {
+            numbers: NumberStyle {
+                decimal_comma: true,
+                group_digits: true,
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            european.format(&tree),
+            r"1\,234\,567{,}891\,011 + 3{,}14 + 1000"
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let tree = AsciiParser::default()
+            .parse(&"lim_(x->0) sin(x)/x - lim_(t -> 1^-) (t + 1)")
+            .unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\lim_{x \to 0} \frac{ \sin\left(x\right) }{ x } - \lim_{t \to 1^{-}} (t + 1)"
+        );
+    }
 }
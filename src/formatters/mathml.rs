@@ -0,0 +1,522 @@
+//! A Formatter for Presentation MathML.
+
+use crate::{
+    ast::{self, SymbolBinaryOp},
+    delimiter::{self, DelimKind},
+    formatter::{Formatter, Grouping},
+    formatters::precedence::{
+        big_op_needs_parens, chain_needs_parens, derivative_needs_parens, leibniz_form,
+        need_parens, postfix_needs_parens, quantifier_needs_parens,
+    },
+    operators::Op,
+    symbols,
+};
+
+/// The invisible function application operator, placed between a function and its arguments.
+const FUNCTION_APPLICATION: &str = "\u{2061}";
+
+/// The invisible times operator, placed between juxtaposed arguments.
+const INVISIBLE_TIMES: &str = "\u{2062}";
+
+/// The invisible plus operator, placed between the whole part and the fraction of a mixed number.
+const INVISIBLE_PLUS: &str = "\u{2064}";
+
+/// Escapes the characters that have special meaning in XML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Wraps MathML in parentheses.
+fn fenced(inner: &str) -> String {
+    format!("<mrow><mo>(</mo>{}<mo>)</mo></mrow>", inner)
+}
+
+/// A formatter for Presentation MathML, the kind of MathML that browsers render natively. The output
+/// of `format` is a fragment: use `format_math` to get a complete `<math>` element.
+#[derive(Default)]
+pub struct MathMlFormatter {
+    /// How to deal with the groups written in the input, like the parentheses in (a + b) c.
+    pub grouping: Grouping,
+}
+
+impl MathMlFormatter {
+    /// Formats an `AST` as a complete `<math>` element that can be put directly into HTML.
+    pub fn format_math(&mut self, ast: &ast::AST) -> String {
+        format!(
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">{}</math>",
+            self.format(ast)
+        )
+    }
+
+    /// Formats a symbol used as an operator.
+    fn format_operator(&mut self, sym: &ast::Symbol) -> String {
+        format!("<mo>{}</mo>", escape(&sym.unicode_repr))
+    }
+}
+
+impl Formatter for MathMlFormatter {
+    type Output = String;
+
+    fn grouping(&self) -> Grouping {
+        self.grouping
+    }
+
+    fn format_symbol(&mut self, sym: &ast::Symbol) -> Self::Output {
+        let text = &sym.unicode_repr;
+        if !text.is_empty() && text.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            format!("<mn>{}</mn>", text)
+        } else if text.chars().any(char::is_alphabetic) || sym == &*symbols::INF {
+            format!("<mi>{}</mi>", escape(text))
+        } else {
+            self.format_operator(sym)
+        }
+    }
+
+    fn format_number(&mut self, num: &ast::Number) -> Self::Output {
+        let mantissa = match &num.repeating {
+            Some(repeating) => format!(
+                "<mrow><mn>{}</mn><mover accent=\"true\"><mn>{}</mn><mo>‾</mo></mover></mrow>",
+                num.mantissa, repeating
+            ),
+            None => format!("<mn>{}</mn>", num.mantissa),
+        };
+        match &num.exponent {
+            Some(exponent) => {
+                let exponent = match exponent.strip_prefix('-') {
+                    Some(digits) => format!("<mrow><mo>−</mo><mn>{}</mn></mrow>", digits),
+                    None => format!("<mn>{}</mn>", exponent),
+                };
+                format!(
+                    "<mrow>{}<mo>×</mo><msup><mn>10</mn>{}</msup></mrow>",
+                    mantissa, exponent
+                )
+            }
+            None => mantissa,
+        }
+    }
+
+    fn format_mixed_number(
+        &mut self,
+        whole: Option<&str>,
+        numerator: &str,
+        denominator: &str,
+    ) -> Self::Output {
+        let fraction = format!(
+            "<mfrac><mn>{}</mn><mn>{}</mn></mfrac>",
+            numerator, denominator
+        );
+        match whole {
+            Some(whole) => format!(
+                "<mrow><mn>{}</mn><mo>{}</mo>{}</mrow>",
+                whole, INVISIBLE_PLUS, fraction
+            ),
+            None => fraction,
+        }
+    }
+
+    fn format_binary_expr(
+        &mut self,
+        op: &ast::BinaryOp,
+        arg1: &ast::AST,
+        arg2: &ast::AST,
+    ) -> Self::Output {
+        let (left_p, right_p) = need_parens(op, arg1, arg2);
+        let left_no_paren = self.format(arg1);
+        let left = if left_p {
+            fenced(&left_no_paren)
+        } else {
+            left_no_paren.clone()
+        };
+        let right_no_paren = self.format(arg2);
+        let right = if right_p {
+            fenced(&right_no_paren)
+        } else {
+            right_no_paren.clone()
+        };
+        match op {
+            ast::BinaryOp::Generic(SymbolBinaryOp { op, fixity }) => {
+                let symbol = self.format_operator(&op.sym);
+                match fixity {
+                    ast::Fixity::Prefix => format!("<mrow>{}{}{}</mrow>", symbol, left, right),
+                    ast::Fixity::Infix => format!("<mrow>{}{}{}</mrow>", left, symbol, right),
+                    ast::Fixity::Postfix => format!("<mrow>{}{}{}</mrow>", left, right, symbol),
+                }
+            }
+            // a subscripted base gets both scripts on the same element, like x_i^2
+            ast::BinaryOp::Power => match arg1 {
+                ast::AST::BinaryExpr(ast::BinaryOp::Subscript, base, sub) => format!(
+                    "<msubsup>{}{}{}</msubsup>",
+                    self.format(base),
+                    self.format(sub),
+                    right_no_paren
+                ),
+                // the superscript takes care of grouping the exponent
+                _ => format!("<msup>{}{}</msup>", left, right_no_paren),
+            },
+            ast::BinaryOp::Subscript => format!("<msub>{}{}</msub>", left, right_no_paren),
+            // fractions never need parentheses for their arguments
+            ast::BinaryOp::Frac => format!("<mfrac>{}{}</mfrac>", left_no_paren, right_no_paren),
+            ast::BinaryOp::Log => format!(
+                "<mrow><msub><mi>log</mi>{}</msub><mo>{}</mo>{}</mrow>",
+                left_no_paren,
+                FUNCTION_APPLICATION,
+                fenced(&right_no_paren)
+            ),
+            ast::BinaryOp::Root => {
+                if left_no_paren == "<mn>2</mn>" {
+                    format!("<msqrt>{}</msqrt>", right_no_paren)
+                } else {
+                    format!("<mroot>{}{}</mroot>", right_no_paren, left_no_paren)
+                }
+            }
+            ast::BinaryOp::Concat => {
+                let op = if ast::is_application(arg1, arg2) {
+                    FUNCTION_APPLICATION
+                } else {
+                    INVISIBLE_TIMES
+                };
+                format!("<mrow>{}<mo>{}</mo>{}</mrow>", left, op, right)
+            }
+        }
+    }
+
+    fn format_unary_expr(&mut self, op: &ast::UnaryOp, arg: &ast::AST) -> Self::Output {
+        let arg_no_paren = self.format(arg);
+        match op {
+            ast::UnaryOp::Generic(sym) => {
+                let sym = self.format_operator(sym);
+                format!("<mrow>{}{}</mrow>", sym, arg_no_paren)
+            }
+            ast::UnaryOp::Postfix(sym) => {
+                let arg = if postfix_needs_parens(sym, arg) {
+                    fenced(&arg_no_paren)
+                } else {
+                    arg_no_paren
+                };
+                // degrees and transposes are raised, like exponents
+                if sym == &*symbols::DEGREE {
+                    format!("<msup>{}<mo>{}</mo></msup>", arg, sym.unicode_repr)
+                } else if sym == &*symbols::TRANSPOSE {
+                    format!("<msup>{}<mi>T</mi></msup>", arg)
+                } else {
+                    format!("<mrow>{}{}</mrow>", arg, self.format_operator(sym))
+                }
+            }
+        }
+    }
+
+    fn format_function(&mut self, name: &ast::Symbol, args: &[ast::AST]) -> Self::Output {
+        let name = format!("<mi>{}</mi>", escape(&name.unicode_repr));
+        let args: Vec<String> = args.iter().map(|ast| self.format(ast)).collect();
+        format!(
+            "<mrow>{}<mo>{}</mo>{}</mrow>",
+            name,
+            FUNCTION_APPLICATION,
+            fenced(&args.join("<mo>,</mo>"))
+        )
+    }
+
+    fn format_big_op(
+        &mut self,
+        op: &ast::Symbol,
+        lower: Option<&ast::AST>,
+        upper: Option<&ast::AST>,
+        body: &ast::AST,
+    ) -> Self::Output {
+        // integrals put their bounds to the side, and the others put them above and below
+        let (both, below, above) = if op == &*symbols::INT {
+            ("msubsup", "msub", "msup")
+        } else {
+            ("munderover", "munder", "mover")
+        };
+        let symbol = self.format_operator(op);
+        let scripted = match (lower, upper) {
+            (Some(lower), Some(upper)) => format!(
+                "<{}>{}{}{}</{}>",
+                both,
+                symbol,
+                self.format(lower),
+                self.format(upper),
+                both
+            ),
+            (Some(lower), None) => {
+                format!("<{}>{}{}</{}>", below, symbol, self.format(lower), below)
+            }
+            (None, Some(upper)) => {
+                format!("<{}>{}{}</{}>", above, symbol, self.format(upper), above)
+            }
+            (None, None) => symbol,
+        };
+        let body_no_paren = self.format(body);
+        let body = if big_op_needs_parens(body) {
+            fenced(&body_no_paren)
+        } else {
+            body_no_paren
+        };
+        format!("<mrow>{}{}</mrow>", scripted, body)
+    }
+
+    fn format_limit(
+        &mut self,
+        var: &ast::AST,
+        target: &ast::AST,
+        direction: Option<ast::Direction>,
+        body: &ast::AST,
+    ) -> Self::Output {
+        let target = match direction {
+            Some(ast::Direction::Above) => {
+                format!("<msup>{}<mo>+</mo></msup>", self.format(target))
+            }
+            Some(ast::Direction::Below) => {
+                format!("<msup>{}<mo>−</mo></msup>", self.format(target))
+            }
+            None => self.format(target),
+        };
+        let body_no_paren = self.format(body);
+        let body = if big_op_needs_parens(body) {
+            fenced(&body_no_paren)
+        } else {
+            body_no_paren
+        };
+        format!(
+            "<mrow><munder><mo>lim</mo><mrow>{}{}{}</mrow></munder>{}</mrow>",
+            self.format(var),
+            self.format_operator(&symbols::TO),
+            target,
+            body
+        )
+    }
+
+    fn format_derivative(
+        &mut self,
+        kind: ast::DerivativeKind,
+        order: u32,
+        function: &ast::AST,
+        var: &ast::AST,
+    ) -> Self::Output {
+        let d = match kind {
+            ast::DerivativeKind::Ordinary => "<mi>d</mi>".to_string(),
+            ast::DerivativeKind::Partial => self.format_operator(&symbols::PARTIAL),
+        };
+        let (d_n, var) = if order == 1 {
+            (d.clone(), self.format(var))
+        } else {
+            let order = self.format_number(&ast::Number::from(order.to_string().as_str()));
+            (
+                format!("<msup>{}{}</msup>", d, order),
+                format!("<msup>{}{}</msup>", self.format(var), order),
+            )
+        };
+        if leibniz_form(function) {
+            format!(
+                "<mfrac><mrow>{}{}</mrow><mrow>{}{}</mrow></mfrac>",
+                d_n,
+                self.format(function),
+                d,
+                var
+            )
+        } else {
+            let body_no_paren = self.format(function);
+            let body = if derivative_needs_parens(function) {
+                fenced(&body_no_paren)
+            } else {
+                body_no_paren
+            };
+            format!(
+                "<mrow><mfrac>{}<mrow>{}{}</mrow></mfrac>{}</mrow>",
+                d_n, d, var, body
+            )
+        }
+    }
+
+    fn format_prime(&mut self, arg: &ast::AST, count: u32) -> Self::Output {
+        let arg_no_paren = self.format(arg);
+        let arg = if postfix_needs_parens(&symbols::PRIME, arg) {
+            fenced(&arg_no_paren)
+        } else {
+            arg_no_paren
+        };
+        format!("<msup>{}<mo>{}</mo></msup>", arg, symbols::primes(count))
+    }
+    fn format_chain(&mut self, first: &ast::AST, rest: &[(Op, ast::AST)]) -> Self::Output {
+        let parens = chain_needs_parens(first, rest);
+        let mut operands = std::iter::once(first)
+            .chain(rest.iter().map(|(_, operand)| operand))
+            .zip(parens)
+            .map(|(operand, parens)| {
+                let operand = self.format(operand);
+                if parens {
+                    fenced(&operand)
+                } else {
+                    operand
+                }
+            })
+            .collect::<Vec<String>>()
+            .into_iter();
+        let mut output = operands.next().unwrap();
+        for (op, _) in rest {
+            output = format!(
+                "{}{}{}",
+                output,
+                self.format_operator(&op.sym),
+                operands.next().unwrap()
+            );
+        }
+        format!("<mrow>{}</mrow>", output)
+    }
+
+    fn format_quantifier(
+        &mut self,
+        quantifier: &ast::Symbol,
+        binding: &ast::AST,
+        body: &ast::AST,
+    ) -> Self::Output {
+        let body_no_paren = self.format(body);
+        let body = if quantifier_needs_parens(body) {
+            fenced(&body_no_paren)
+        } else {
+            body_no_paren
+        };
+        format!(
+            "<mrow>{}{}<mo>,</mo>{}</mrow>",
+            self.format_operator(quantifier),
+            self.format(binding),
+            body
+        )
+    }
+
+    fn format_set(&mut self, elements: &[ast::AST]) -> Self::Output {
+        let elements: Vec<String> = elements.iter().map(|ast| self.format(ast)).collect();
+        format!(
+            "<mrow><mo>{{</mo>{}<mo>}}</mo></mrow>",
+            elements.join("<mo>,</mo>")
+        )
+    }
+
+    fn format_set_builder(&mut self, members: &ast::AST, condition: &ast::AST) -> Self::Output {
+        format!(
+            "<mrow><mo>{{</mo>{}<mo>|</mo>{}<mo>}}</mo></mrow>",
+            self.format(members),
+            self.format(condition)
+        )
+    }
+
+    fn format_fenced(&mut self, kind: DelimKind, inner: &ast::AST) -> Self::Output {
+        let (left, right) = delimiter::pair(kind);
+        format!(
+            "<mrow>{}{}{}</mrow>",
+            self.format_operator(&left),
+            self.format(inner),
+            self.format_operator(&right)
+        )
+    }
+
+    fn format_group(&mut self, kind: DelimKind, inner: &ast::AST) -> Self::Output {
+        let (left, right) = delimiter::pair(kind);
+        format!(
+            "<mrow>{}{}{}</mrow>",
+            self.format_operator(&left),
+            self.format(inner),
+            self.format_operator(&right)
+        )
+    }
+
+    fn format_matrix(&mut self, rows: &[Vec<ast::AST>]) -> Self::Output {
+        let mut table = String::new();
+        for row in rows {
+            table.push_str("<mtr>");
+            for elem in row {
+                table.push_str(&format!("<mtd>{}</mtd>", self.format(elem)));
+            }
+            table.push_str("</mtr>");
+        }
+        fenced(&format!("<mtable>{}</mtable>", table))
+    }
+
+    fn format_piecewise(&mut self, cases: &[(ast::AST, Option<ast::AST>)]) -> Self::Output {
+        // a brace on the left only, with the conditions in their own column
+        let mut table = String::new();
+        for (value, condition) in cases {
+            let condition = match condition {
+                Some(condition) => {
+                    format!(
+                        "<mtext>if</mtext><mspace width=\"1em\"/>{}",
+                        self.format(condition)
+                    )
+                }
+                None => "<mtext>otherwise</mtext>".to_string(),
+            };
+            table.push_str(&format!(
+                "<mtr><mtd>{}</mtd><mtd>{}</mtd></mtr>",
+                self.format(value),
+                condition
+            ));
+        }
+        format!("<mrow><mo>{{</mo><mtable>{}</mtable></mrow>", table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::{ASTParser, AsciiParser};
+
+    #[test]
+    fn test_parse() {
+        let parser = AsciiParser::default();
+        let tree = parser.parse(&"2 / (sin mu + 1)").unwrap();
+        assert_eq!(
+            MathMlFormatter::default().format(&tree),
+            "<mfrac><mn>2</mn><mrow><mrow><mi>sin</mi><mo>\u{2061}</mo><mrow><mo>(</mo>\
+             <mi>μ</mi><mo>)</mo></mrow></mrow><mo>+</mo><mn>1</mn></mrow></mfrac>"
+        );
+        let tree = parser.parse(&"(a + b) * c ^ (2 - x)").unwrap();
+        assert_eq!(
+            MathMlFormatter::default().format(&tree),
+            "<mrow><mrow><mo>(</mo><mrow><mi>a</mi><mo>+</mo><mi>b</mi></mrow><mo>)</mo></mrow>\
+             <mo>·</mo><msup><mi>c</mi><mrow><mn>2</mn><mo>−</mo><mi>x</mi></mrow></msup></mrow>"
+        );
+    }
+
+    #[test]
+    fn test_applications() {
+        // the parentheses stay, and what's before them is applied like a function
+        let tree = AsciiParser::default().parse(&"f(x)").unwrap();
+        assert_eq!(
+            MathMlFormatter::default().format(&tree),
+            "<mrow><mi>f</mi><mo>\u{2061}</mo><mrow><mo>(</mo><mi>x</mi><mo>)</mo></mrow></mrow>"
+        );
+        let tree = AsciiParser::default().parse(&"2 (x)").unwrap();
+        assert_eq!(
+            MathMlFormatter::default().format(&tree),
+            "<mrow><mn>2</mn><mo>\u{2062}</mo><mi>x</mi></mrow>"
+        );
+    }
+
+    #[test]
+    fn test_math_element() {
+        let tree = AsciiParser::default().parse(&"2 x").unwrap();
+        assert_eq!(
+            MathMlFormatter::default().format_math(&tree),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mrow><mn>2</mn>\
+             <mo>\u{2062}</mo><mi>x</mi></mrow></math>"
+        );
+        let tree = AsciiParser::default().parse(&"x_i^2 + y_1").unwrap();
+        assert_eq!(
+            MathMlFormatter::default().format(&tree),
+            "<mrow><msubsup><mi>x</mi><mi>i</mi><mn>2</mn></msubsup><mo>+</mo>\
+             <msub><mi>y</mi><mn>1</mn></msub></mrow>"
+        );
+        let tree = AsciiParser::default().parse(&"sum_k^n k").unwrap();
+        assert_eq!(
+            MathMlFormatter::default().format(&tree),
+            "<mrow><munderover><mo>∑</mo><mi>k</mi><mi>n</mi></munderover><mi>k</mi></mrow>"
+        );
+        assert_eq!(
+            MathMlFormatter::default().format(&ast::AST::Sym(ast::Symbol::from("<"))),
+            "<mo>&lt;</mo>"
+        );
+    }
+}
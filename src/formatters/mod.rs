@@ -1,5 +1,7 @@
 //! This module provides all of the formatters supported by this crate.
 
+pub mod ascii;
 pub mod latex;
+pub mod mathml;
 pub mod precedence;
 pub mod unicode;
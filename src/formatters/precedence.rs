@@ -1,6 +1,7 @@
 //! Helpers to deal with precedence and parentheses.
 use crate::{
-    ast::{BinaryOp, SymbolBinaryOp, AST},
+    ast::{BinaryOp, Symbol, SymbolBinaryOp, UnaryOp, AST},
+    delimiter::DelimKind,
     operators::{self, Op},
 };
 
@@ -25,8 +26,13 @@ pub fn need_parens(bin_op: &BinaryOp, lchild: &AST, rchild: &AST) -> (bool, bool
             fixity: _,
         }) => (
             match lchild {
-                AST::Sym(_) => false,    // a + b is fine
-                AST::Number(_) => false, // 2 + 3 is fine
+                AST::Sym(_) => false, // a + b is fine
+                // 2 + 3 is fine, but numbers in scientific notation are products: (1.2e3)^2
+                AST::Number(num) => {
+                    num.exponent.is_some() && prec_gt(&operators::MULT.r_prec, l_prec)
+                }
+                // mixed numbers bind the same way: (1 1/2)^2
+                AST::MixedNumber(_, _, _) => prec_gt(&operators::MULT.r_prec, l_prec),
                 // for binary operations: if the corresponding
                 // precedence is higher than the precedence of
                 // this operator, then parenthese are needed.
@@ -40,16 +46,49 @@ pub fn need_parens(bin_op: &BinaryOp, lchild: &AST, rchild: &AST) -> (bool, bool
                         fixity: _,
                     }) => prec_gt(&l_op.r_prec, l_prec),
                     BinaryOp::Power => prec_gt(&operators::POWER.r_prec, l_prec),
+                    BinaryOp::Subscript => prec_gt(&operators::SUBSCRIPT.r_prec, l_prec),
                     BinaryOp::Frac => prec_gt(&operators::DIV.r_prec, l_prec),
                     BinaryOp::Log => prec_gt(&operators::POWER.r_prec, l_prec),
-                    BinaryOp::Concat => false,
+                    BinaryOp::Root => false, // roots group their own arguments, like functions
+                    BinaryOp::Concat => prec_gt(&operators::CONCAT.r_prec, l_prec),
                 }, // (a + 2)b needs parens
-                AST::UnaryExpr(_, _) => false, // -ab doesn't need one, but future might
-                AST::Function(_, _) => false,  // sin(x)a is fine
+                // -a + b is fine, but (not a) = b needs parens because not takes whole relations
+                AST::UnaryExpr(UnaryOp::Generic(sym), _) => prec_gt(&unary_op(sym).r_prec, l_prec),
+                AST::UnaryExpr(UnaryOp::Postfix(_), _) => false, // n! + 1 is fine
+                AST::Function(_, _) => false,                    // sin(x)a is fine
+                // a big operator takes everything that binds tightly enough: sum_i i + 1 is fine,
+                // but (sum_i i) * 2 needs parens
+                AST::BigOp(_, _, _, _) | AST::Limit(_, _, _, _) => {
+                    !prec_gt(l_prec, &operators::BIG_OP.r_prec)
+                }
+                // dy/dx works like a fraction, and d/dx (x^2) works like a sum
+                AST::Derivative(_, _, function, _) => {
+                    if leibniz_form(function) {
+                        prec_gt(&operators::DIV.r_prec, l_prec)
+                    } else {
+                        !prec_gt(l_prec, &operators::BIG_OP.r_prec)
+                    }
+                }
+                AST::Prime(_, _) => false, // f' + 1 is fine
+                // chains work like any other relation
+                AST::Chain(_, _) => prec_gt(&operators::EQ.r_prec, l_prec),
+                // quantifiers take everything up to a comma, like big operators
+                AST::Quantifier(_, _, _) => !prec_gt(l_prec, &operators::QUANTIFIER.r_prec),
+                // sets, matrices and absolute values group their own insides, like functions, and
+                // groups already have their delimiters
+                AST::Set(_)
+                | AST::SetBuilder(_, _)
+                | AST::Fenced(_, _)
+                | AST::Group(_, _)
+                | AST::Matrix(_)
+                | AST::Piecewise(_) => false,
             },
             match rchild {
-                AST::Sym(_) => false,    // a + b is fine
-                AST::Number(_) => false, // 2 + 3 is fine
+                AST::Sym(_) => false, // a + b is fine
+                AST::Number(num) => {
+                    num.exponent.is_some() && prec_gt(&operators::MULT.l_prec, r_prec)
+                }
+                AST::MixedNumber(_, _, _) => prec_gt(&operators::MULT.l_prec, r_prec),
                 // for binary operations: if the corresponding
                 // precedence is higher than the precedence of
                 // this operator, then parenthese are needed.
@@ -63,12 +102,28 @@ pub fn need_parens(bin_op: &BinaryOp, lchild: &AST, rchild: &AST) -> (bool, bool
                         fixity: _,
                     }) => prec_gt(&l_op.l_prec, r_prec),
                     BinaryOp::Power => prec_gt(&operators::POWER.l_prec, r_prec),
+                    BinaryOp::Subscript => prec_gt(&operators::SUBSCRIPT.l_prec, r_prec),
                     BinaryOp::Frac => prec_gt(&operators::DIV.l_prec, r_prec),
                     BinaryOp::Log => prec_gt(&operators::POWER.l_prec, r_prec),
-                    BinaryOp::Concat => false,
+                    BinaryOp::Root => false, // roots group their own arguments, like functions
+                    BinaryOp::Concat => prec_gt(&operators::CONCAT.l_prec, r_prec),
                 }, // (a + 2)b needs parens
                 AST::UnaryExpr(_, _) => false, // -ab doesn't need one, but future might
                 AST::Function(_, _) => false,  // sin(x)a is fine
+                AST::BigOp(_, _, _, _) => false, // 2 * sum_i i is fine
+                AST::Limit(_, _, _, _) => false,
+                AST::Derivative(_, _, function, _) => {
+                    leibniz_form(function) && prec_gt(&operators::DIV.l_prec, r_prec)
+                }
+                AST::Prime(_, _) => false,
+                AST::Chain(_, _) => prec_gt(&operators::EQ.l_prec, r_prec),
+                AST::Quantifier(_, _, _) => false, // p and forall x, q is fine
+                AST::Set(_)
+                | AST::SetBuilder(_, _)
+                | AST::Fenced(_, _)
+                | AST::Group(_, _)
+                | AST::Matrix(_)
+                | AST::Piecewise(_) => false,
             },
         ),
         // defer others to the normal symbol versions
@@ -81,6 +136,14 @@ pub fn need_parens(bin_op: &BinaryOp, lchild: &AST, rchild: &AST) -> (bool, bool
             lchild,
             rchild,
         ),
+        BinaryOp::Subscript => need_parens(
+            &BinaryOp::Generic(SymbolBinaryOp {
+                op: operators::SUBSCRIPT.to_owned(),
+                fixity: crate::ast::Fixity::Infix,
+            }),
+            lchild,
+            rchild,
+        ),
         BinaryOp::Frac => need_parens(
             &BinaryOp::Generic(SymbolBinaryOp {
                 op: operators::DIV.to_owned(),
@@ -98,25 +161,191 @@ pub fn need_parens(bin_op: &BinaryOp, lchild: &AST, rchild: &AST) -> (bool, bool
             lchild,
             rchild,
         ),
+        // formats write roots so that their arguments are grouped already: √(x + 1), \sqrt{x + 1}
+        BinaryOp::Root => (false, false),
+        // the same tree comes out of either ImplicitMultiplication setting, and subscripts, powers
+        // and roots bind tighter than TIGHT_CONCAT too, so these rules hold for both
         BinaryOp::Concat => (
             match lchild {
-                AST::Sym(_) => false,             // ab is fine
-                AST::Number(_) => false,          // 2a is fine
+                AST::Sym(_) => false,               // ab is fine
+                AST::Number(_) => false,            // 2a is fine
+                AST::MixedNumber(_, _, _) => false, // 1 1/2 x is fine
+                // x_1 y, x^2 y and √(2)x are fine, and so is a b c
+                AST::BinaryExpr(
+                    BinaryOp::Subscript | BinaryOp::Power | BinaryOp::Root | BinaryOp::Concat,
+                    _,
+                    _,
+                ) => false,
                 AST::BinaryExpr(_, _, _) => true, // (a + 2)b needs parens
                 AST::UnaryExpr(_, _) => false,    // -ab doesn't need one, but future might
                 AST::Function(_, _) => false,     // sin(x)a is fine
+                AST::BigOp(_, _, _, _) => true,   // (sum_i i) a needs parens
+                AST::Limit(_, _, _, _) => true,
+                AST::Derivative(_, _, _, _) => true, // (dy/dx)x needs parens
+                AST::Prime(_, _) => false,           // f'(x) y is fine
+                AST::Chain(_, _) => true,
+                AST::Quantifier(_, _, _) => true,
+                // |x| y and (a + b) c are fine
+                AST::Set(_)
+                | AST::SetBuilder(_, _)
+                | AST::Fenced(_, _)
+                | AST::Group(_, _)
+                | AST::Matrix(_)
+                | AST::Piecewise(_) => false,
             },
             match rchild {
-                AST::Sym(_) => false,             // ab is fine
-                AST::Number(_) => false,          // a2 is fine, if weird
+                // c (a + b) and f(x) are fine, and otherwise a primed function is applied to what
+                // comes after it in parentheses: f''(x)
+                AST::Group(_, _) | AST::Fenced(DelimKind::Paren, _) => false,
+                _ if matches!(lchild, AST::Prime(_, _)) => true,
+                AST::Sym(_) => false, // ab is fine
+                // a2 is fine, if weird, but a (1.2e3) isn't
+                AST::Number(num) => num.exponent.is_some(),
+                AST::MixedNumber(_, _, _) => true, // 2 (1 1/2) needs parens
+                // y x_1, 2 x^2 and x√(2) are fine: powers bind tighter than juxtaposition
+                AST::BinaryExpr(BinaryOp::Subscript | BinaryOp::Power | BinaryOp::Root, _, _) => {
+                    false
+                }
                 AST::BinaryExpr(_, _, _) => true, // b(2 + a) needs parens
+                AST::UnaryExpr(UnaryOp::Postfix(_), _) => false, // 2 n! is fine
                 AST::UnaryExpr(_, _) => true,     // b(-a) needs parens
                 AST::Function(_, _) => false,     // a sin(x) needs no parens
+                AST::BigOp(_, _, _, _) => false,  // a sum_i i needs no parens
+                AST::Limit(_, _, _, _) => false,
+                AST::Derivative(_, _, _, _) => false, // 2 dy/dx is fine
+                AST::Prime(_, _) => false,            // x f' is fine
+                AST::Chain(_, _) => true,
+                AST::Quantifier(_, _, _) => true, // p (forall x, q) needs parens
+                // 2|x| is fine
+                AST::Set(_)
+                | AST::SetBuilder(_, _)
+                | AST::Fenced(_, _)
+                | AST::Matrix(_)
+                | AST::Piecewise(_) => false,
             },
         ),
     }
 }
 
+/// Returns whether each operand in a chain of relations, like 0 < x + 1 < 2, needs parentheses,
+/// starting with the first one.
+pub fn chain_needs_parens(first: &AST, rest: &[(Op, AST)]) -> Vec<bool> {
+    // each operand works like the right side of the relation before it, except for the first
+    let relation = |op: &Op| {
+        BinaryOp::Generic(SymbolBinaryOp {
+            op: op.clone(),
+            fixity: crate::ast::Fixity::Infix,
+        })
+    };
+    let mut parens = vec![rest
+        .first()
+        .is_some_and(|(op, _)| need_parens(&relation(op), first, first).0)];
+    parens.extend(
+        rest.iter()
+            .map(|(op, operand)| need_parens(&relation(op), operand, operand).1),
+    );
+    parens
+}
+
+/// Returns whether the derivative of an expression is written in Leibniz form, like dy/dx, instead
+/// of as an operator applied to the expression, like d/dx (x^2).
+pub fn leibniz_form(function: &AST) -> bool {
+    matches!(function, AST::Sym(_))
+}
+
+/// Returns whether the body of a big operator, like the i + 1 in sum_i (i + 1), needs parentheses.
+pub fn big_op_needs_parens(body: &AST) -> bool {
+    // the body works like the right side of an operator with the big operator's precedence
+    need_parens(
+        &BinaryOp::Generic(SymbolBinaryOp {
+            op: operators::BIG_OP.to_owned(),
+            fixity: crate::ast::Fixity::Prefix,
+        }),
+        &AST::Sym(crate::symbols::SUM.clone()),
+        body,
+    )
+    .1
+}
+
+/// Returns whether the statement a quantifier applies to, like the p, q in forall x, (p, q), needs
+/// parentheses.
+pub fn quantifier_needs_parens(body: &AST) -> bool {
+    need_parens(
+        &BinaryOp::Generic(SymbolBinaryOp {
+            op: operators::QUANTIFIER.to_owned(),
+            fixity: crate::ast::Fixity::Prefix,
+        }),
+        &AST::Sym(crate::symbols::FORALL.clone()),
+        body,
+    )
+    .1
+}
+
+/// Finds the unary operator written with a symbol. Anything without its own precedence binds like
+/// unary minus.
+fn unary_op(sym: &Symbol) -> &'static Op {
+    operators::UNARY_OPS
+        .iter()
+        .find(|op| &op.sym == sym)
+        .unwrap_or(&operators::UNARY_MINUS)
+}
+
+/// Returns whether the argument of a prefix unary operator, like the a and b in not (a and b),
+/// needs parentheses.
+pub fn unary_needs_parens(sym: &Symbol, arg: &AST) -> bool {
+    // a sign in front of a number is part of it, even in scientific notation: -1.2e-3
+    if let AST::Number(_) | AST::MixedNumber(_, _, _) = arg {
+        return false;
+    }
+    // the argument works like the right side of a binary operator with nothing on the left
+    need_parens(
+        &BinaryOp::Generic(SymbolBinaryOp {
+            op: unary_op(sym).to_owned(),
+            fixity: crate::ast::Fixity::Prefix,
+        }),
+        arg,
+        arg,
+    )
+    .1
+}
+
+/// Returns whether the body of a derivative written as an operator, like the x^2 in d/dx (x^2),
+/// needs parentheses. Only single symbols and function calls go without them, because d/dx x^2
+/// reads like it's about the x.
+pub fn derivative_needs_parens(body: &AST) -> bool {
+    !matches!(
+        body,
+        AST::Sym(_)
+            | AST::Number(_)
+            | AST::MixedNumber(_, _, _)
+            | AST::Function(_, _)
+            | AST::Prime(_, _)
+            | AST::Group(_, _)
+            | AST::Matrix(_)
+            | AST::Piecewise(_)
+    )
+}
+
+/// Returns whether the argument of a postfix operator, like the n + 1 in (n + 1)!, needs
+/// parentheses. Prime marks work the same way: (x^2)'.
+pub fn postfix_needs_parens(sym: &Symbol, arg: &AST) -> bool {
+    // anything without its own precedence binds like the factorial
+    let op = operators::POSTFIX_OPS
+        .iter()
+        .find(|op| &op.sym == sym)
+        .unwrap_or(&operators::FACTORIAL);
+    // the argument works like the left side of a binary operator with nothing on the right
+    need_parens(
+        &BinaryOp::Generic(SymbolBinaryOp {
+            op: op.to_owned(),
+            fixity: crate::ast::Fixity::Postfix,
+        }),
+        arg,
+        arg,
+    )
+    .0
+}
+
 #[cfg(test)]
 mod tests {
 
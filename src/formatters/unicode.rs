@@ -2,75 +2,492 @@
 
 use crate::{
     ast::{self, SymbolBinaryOp},
-    formatters::precedence::need_parens,
+    delimiter::{self, DelimKind},
+    formatter::{Grouping, NumberStyle},
+    formatters::precedence::{
+        big_op_needs_parens, chain_needs_parens, derivative_needs_parens, leibniz_form,
+        need_parens, postfix_needs_parens, quantifier_needs_parens, unary_needs_parens,
+    },
     operators::Op,
+    symbols,
 };
 
+/// Writes a subscript using Unicode subscript characters if they all exist (`x₁`), falling back
+/// to an underscore otherwise (`x_q`).
+fn subscript(sub: &str, sub_with_parens: &str) -> String {
+    match sub
+        .chars()
+        .map(symbols::to_subscript)
+        .collect::<Option<String>>()
+    {
+        Some(sub) => sub,
+        None => format!("_{}", sub_with_parens),
+    }
+}
+
+/// Puts a block that may span several lines after a prefix on its first line, indenting the
+/// other lines to match so that multi-line output like a piecewise function stays aligned.
+fn hang(prefix: &str, block: &str) -> String {
+    let indent = " ".repeat(prefix.lines().last().unwrap_or("").chars().count());
+    let mut lines = block.lines();
+    let mut output = format!("{}{}", prefix, lines.next().unwrap_or(""));
+    for line in lines {
+        output.push('\n');
+        output.push_str(&indent);
+        output.push_str(line);
+    }
+    output
+}
+
+/// Writes a superscript using Unicode superscript characters if they all exist (`∑ⁿ`), falling
+/// back to a caret otherwise (`∑^∞`).
+fn superscript(sup: &str, sup_with_parens: &str) -> String {
+    match sup
+        .chars()
+        .map(symbols::to_superscript)
+        .collect::<Option<String>>()
+    {
+        Some(sup) => sup,
+        None => format!("^{}", sup_with_parens),
+    }
+}
+
+/// Returns whether the operand on one edge of a product, its last one if `last` is set and its first
+/// otherwise, is a name of more than one letter, like `rate`, which would run into a letter next
+/// to it.
+fn edge_is_word(tree: &ast::AST, last: bool) -> bool {
+    match tree {
+        ast::AST::Sym(sym) => {
+            sym.unicode_repr.chars().count() > 1
+                && sym.unicode_repr.chars().all(char::is_alphabetic)
+        }
+        ast::AST::BinaryExpr(ast::BinaryOp::Concat, left, right) => {
+            edge_is_word(if last { right } else { left }, last)
+        }
+        _ => false,
+    }
+}
+
 /// A formatter for Unicode that tries to use the Unicode math symbols wherever possible.
 #[derive(Default)]
-pub struct UnicodeFormatter {}
+pub struct UnicodeFormatter {
+    /// How to deal with the groups written in the input, like the parentheses in (a + b) c.
+    pub grouping: Grouping,
+    /// How to write the digits of numbers.
+    pub numbers: NumberStyle,
+}
 
 impl crate::formatter::Formatter for UnicodeFormatter {
     type Output = String;
 
+    fn grouping(&self) -> Grouping {
+        self.grouping
+    }
+
     fn format_symbol(&mut self, sym: &ast::Symbol) -> Self::Output {
         sym.unicode_repr.clone()
     }
 
-    fn format_number(&mut self, dec: &str) -> Self::Output {
-        dec.to_string()
+    fn format_number(&mut self, num: &ast::Number) -> Self::Output {
+        let mut output = self.numbers.format_mantissa(&num.mantissa, ",", "\u{2009}");
+        if let Some(repeating) = &num.repeating {
+            // a combining overline on each repeating digit, like 0.3̅
+            for c in repeating.chars() {
+                output.push(c);
+                output.push('\u{305}');
+            }
+        }
+        if let Some(exponent) = &num.exponent {
+            // every character of an exponent has a superscript form, like 10⁻³
+            let exponent: String = exponent
+                .chars()
+                .filter_map(symbols::to_superscript)
+                .collect();
+            output.push_str(&format!(" × 10{}", exponent));
+        }
+        output
+    }
+
+    fn format_mixed_number(
+        &mut self,
+        whole: Option<&str>,
+        numerator: &str,
+        denominator: &str,
+    ) -> Self::Output {
+        // a vulgar fraction if there is one, like 1½, and digit scripts otherwise, like 1⁵⁄₇
+        let fraction = symbols::unicode_fraction(numerator, denominator);
+        format!("{}{}", whole.unwrap_or_default(), fraction)
     }
 
     fn format_binary_expr(
         &mut self,
         op: &ast::BinaryOp,
-        arg1: &Box<ast::AST>,
-        arg2: &Box<ast::AST>,
+        arg1: &ast::AST,
+        arg2: &ast::AST,
     ) -> Self::Output {
         let (left_p, right_p) = need_parens(op, arg1, arg2);
-        let left_no_paren = self.format(&arg1.to_owned());
+        let left_no_paren = self.format(arg1);
         let left = if left_p {
             format!("({})", left_no_paren)
         } else {
-            format!("{}", left_no_paren)
+            left_no_paren.clone()
         };
-        let right_no_paren = self.format(&arg2.to_owned());
+        let right_no_paren = self.format(arg2);
         let right = if right_p {
             format!("({})", right_no_paren)
         } else {
-            format!("{}", right_no_paren)
+            right_no_paren.clone()
         };
         match op {
             ast::BinaryOp::Generic(SymbolBinaryOp { op, fixity }) => {
                 let symbol = self.format_symbol(&op.sym);
                 match fixity {
                     ast::Fixity::Prefix => format!("{} {} {}", symbol, left, right),
-                    ast::Fixity::Infix => format!("{} {} {}", left, symbol, right),
+                    ast::Fixity::Infix => hang(&format!("{} {} ", left, symbol), &right),
                     ast::Fixity::Postfix => format!("{} {} {}", left, right, symbol),
                 }
             }
             ast::BinaryOp::Power => format!("{}^{}", left, right),
+            ast::BinaryOp::Subscript => {
+                format!("{}{}", left, subscript(&right_no_paren, &right))
+            }
             ast::BinaryOp::Frac => format!("{} / {}", left, right),
-            ast::BinaryOp::Log => format!("log_{} {}", left, right),
+            ast::BinaryOp::Log => {
+                let base = if left_p {
+                    format!("_{}", left)
+                } else {
+                    subscript(&left_no_paren, &left)
+                };
+                format!("log{} {}", base, right)
+            }
+            ast::BinaryOp::Root => {
+                let radicand = match arg2 {
+                    ast::AST::Sym(_) => right_no_paren.clone(),
+                    ast::AST::Number(num) if num.exponent.is_none() => right_no_paren.clone(),
+                    _ => format!("({})", right_no_paren),
+                };
+                let index: Option<String> =
+                    left_no_paren.chars().map(symbols::to_superscript).collect();
+                match (left_no_paren.as_str(), index) {
+                    ("2", _) => format!("√{}", radicand),
+                    ("3", _) => format!("∛{}", radicand),
+                    ("4", _) => format!("∜{}", radicand),
+                    // other indices go in front, like ⁵√x
+                    (_, Some(index)) => format!("{}√{}", index, radicand),
+                    (index, None) => format!("root({}, {})", index, right_no_paren),
+                }
+            }
+            // digits right next to each other would read as one number: 2 · 3, not 23, and
+            // 1.2 · .3, not 1.2.3
+            ast::BinaryOp::Concat
+                if left.ends_with(|c: char| c.is_ascii_digit())
+                    && right.starts_with(|c: char| c.is_ascii_digit() || c == '.') =>
+            {
+                format!("{} · {}", left, right)
+            }
+            // a name right next to a letter would read as a longer name: rate x, not ratex
+            ast::BinaryOp::Concat
+                if (edge_is_word(arg1, true) || edge_is_word(arg2, false))
+                    && left.ends_with(char::is_alphabetic)
+                    && right.starts_with(char::is_alphabetic) =>
+            {
+                format!("{} {}", left, right)
+            }
             ast::BinaryOp::Concat => format!("{}{}", left, right),
         }
     }
 
-    fn format_unary_expr(&mut self, op: &ast::UnaryOp, arg: &Box<ast::AST>) -> Self::Output {
-        let arg = self.format(&arg.to_owned());
+    fn format_unary_expr(&mut self, op: &ast::UnaryOp, arg: &ast::AST) -> Self::Output {
+        let arg_no_paren = self.format(arg);
         match op {
             ast::UnaryOp::Generic(sym) => {
+                let arg = if unary_needs_parens(sym, arg) {
+                    format!("({})", arg_no_paren)
+                } else {
+                    arg_no_paren
+                };
                 let sym = self.format_symbol(sym);
-                format!("({} {})", sym, arg)
+                // whatever's around this adds parentheses if it needs them, and a word needs a
+                // space to keep it apart from the argument: −b, but not p
+                if sym.ends_with(char::is_alphabetic) {
+                    format!("{} {}", sym, arg)
+                } else {
+                    format!("{}{}", sym, arg)
+                }
+            }
+            ast::UnaryOp::Postfix(sym) => {
+                let arg = if postfix_needs_parens(sym, arg) {
+                    format!("({})", arg_no_paren)
+                } else {
+                    arg_no_paren
+                };
+                format!("{}{}", arg, self.format_symbol(sym))
             }
         }
     }
 
-    fn format_function(&mut self, name: &ast::Symbol, args: &Vec<ast::AST>) -> Self::Output {
+    fn format_function(&mut self, name: &ast::Symbol, args: &[ast::AST]) -> Self::Output {
         let name = self.format_symbol(name);
         let args: Vec<String> = args.iter().map(|ast| self.format(ast)).collect();
         format!("{}({})", name, args.join(", "))
     }
+
+    fn format_big_op(
+        &mut self,
+        op: &ast::Symbol,
+        lower: Option<&ast::AST>,
+        upper: Option<&ast::AST>,
+        body: &ast::AST,
+    ) -> Self::Output {
+        let mut scripted = self.format_symbol(op);
+        // bounds that are more than a single symbol need parentheses if they can't be scripts
+        let mut bound = |bound: &ast::AST| {
+            let bound_no_paren = self.format(bound);
+            match bound {
+                ast::AST::Sym(_) => (bound_no_paren.clone(), bound_no_paren),
+                ast::AST::Number(num) if num.exponent.is_none() => {
+                    (bound_no_paren.clone(), bound_no_paren)
+                }
+                _ => (bound_no_paren.clone(), format!("({})", bound_no_paren)),
+            }
+        };
+        let lower = lower.map(&mut bound);
+        let upper = upper.map(&mut bound);
+        // both bounds are Unicode scripts or neither is, so ∑ᵢⁿ or ∑_(i = 1)^n but never a mix
+        let fits = lower
+            .iter()
+            .all(|(text, _)| text.chars().all(|c| symbols::to_subscript(c).is_some()))
+            && upper
+                .iter()
+                .all(|(text, _)| text.chars().all(|c| symbols::to_superscript(c).is_some()));
+        if let Some((lower_no_paren, lower)) = lower {
+            scripted.push_str(&if fits {
+                subscript(&lower_no_paren, &lower)
+            } else {
+                format!("_{}", lower)
+            });
+        }
+        if let Some((upper_no_paren, upper)) = upper {
+            scripted.push_str(&if fits {
+                superscript(&upper_no_paren, &upper)
+            } else {
+                format!("^{}", upper)
+            });
+        }
+        let body = match body.split_differential() {
+            // the differential of an integral gets some space: ∫ f(x) dx
+            Some((integrand, d)) if op == &*symbols::INT => {
+                let integrand = if big_op_needs_parens(&integrand) {
+                    format!("({})", self.format(&integrand))
+                } else {
+                    self.format(&integrand)
+                };
+                format!("{} {}", integrand, self.format_symbol(&d))
+            }
+            _ if big_op_needs_parens(body) => format!("({})", self.format(body)),
+            _ => self.format(body),
+        };
+        format!("{} {}", scripted, body)
+    }
+
+    fn format_limit(
+        &mut self,
+        var: &ast::AST,
+        target: &ast::AST,
+        direction: Option<ast::Direction>,
+        body: &ast::AST,
+    ) -> Self::Output {
+        let direction = match direction {
+            Some(ast::Direction::Above) => "⁺",
+            Some(ast::Direction::Below) => "⁻",
+            None => "",
+        };
+        let body_no_paren = self.format(body);
+        let body = if big_op_needs_parens(body) {
+            format!("({})", body_no_paren)
+        } else {
+            body_no_paren
+        };
+        format!(
+            "{}_{{{}{}{}{}}} {}",
+            self.format_symbol(&symbols::LIM),
+            self.format(var),
+            self.format_symbol(&symbols::TO),
+            self.format(target),
+            direction,
+            body
+        )
+    }
+
+    fn format_derivative(
+        &mut self,
+        kind: ast::DerivativeKind,
+        order: u32,
+        function: &ast::AST,
+        var: &ast::AST,
+    ) -> Self::Output {
+        let d = match kind {
+            ast::DerivativeKind::Ordinary => "d".to_string(),
+            ast::DerivativeKind::Partial => self.format_symbol(&symbols::PARTIAL),
+        };
+        let (d_n, var) = if order == 1 {
+            (d.clone(), self.format(var))
+        } else {
+            let order = order.to_string();
+            let order = superscript(&order, &order);
+            (
+                format!("{}{}", d, order),
+                format!("{}{}", self.format(var), order),
+            )
+        };
+        if leibniz_form(function) {
+            format!("{}{}/{}{}", d_n, self.format(function), d, var)
+        } else {
+            let body_no_paren = self.format(function);
+            let body = if derivative_needs_parens(function) {
+                format!("({})", body_no_paren)
+            } else {
+                body_no_paren
+            };
+            format!("{}/{}{} {}", d_n, d, var, body)
+        }
+    }
+
+    fn format_prime(&mut self, arg: &ast::AST, count: u32) -> Self::Output {
+        let arg_no_paren = self.format(arg);
+        let arg = if postfix_needs_parens(&symbols::PRIME, arg) {
+            format!("({})", arg_no_paren)
+        } else {
+            arg_no_paren
+        };
+        format!("{}{}", arg, symbols::primes(count))
+    }
+    fn format_chain(&mut self, first: &ast::AST, rest: &[(Op, ast::AST)]) -> Self::Output {
+        let parens = chain_needs_parens(first, rest);
+        let mut operands = std::iter::once(first)
+            .chain(rest.iter().map(|(_, operand)| operand))
+            .zip(parens)
+            .map(|(operand, parens)| {
+                let operand = self.format(operand);
+                if parens {
+                    format!("({})", operand)
+                } else {
+                    operand
+                }
+            })
+            .collect::<Vec<String>>()
+            .into_iter();
+        let mut output = operands.next().unwrap();
+        for (op, _) in rest {
+            output = hang(
+                &format!("{} {} ", output, self.format_symbol(&op.sym)),
+                &operands.next().unwrap(),
+            );
+        }
+        output
+    }
+
+    fn format_quantifier(
+        &mut self,
+        quantifier: &ast::Symbol,
+        binding: &ast::AST,
+        body: &ast::AST,
+    ) -> Self::Output {
+        // the symbol goes right up against the variable, as in ∀x
+        let quantifier = self.format_symbol(quantifier);
+        let binding = self.format(binding);
+        let body_no_paren = self.format(body);
+        if quantifier_needs_parens(body) {
+            format!("{}{}, ({})", quantifier, binding, body_no_paren)
+        } else {
+            format!("{}{}, {}", quantifier, binding, body_no_paren)
+        }
+    }
+
+    fn format_set(&mut self, elements: &[ast::AST]) -> Self::Output {
+        let elements: Vec<String> = elements.iter().map(|ast| self.format(ast)).collect();
+        format!("{{{}}}", elements.join(", "))
+    }
+
+    fn format_set_builder(&mut self, members: &ast::AST, condition: &ast::AST) -> Self::Output {
+        format!("{{{} | {}}}", self.format(members), self.format(condition))
+    }
+
+    fn format_fenced(&mut self, kind: DelimKind, inner: &ast::AST) -> Self::Output {
+        let (left, right) = delimiter::pair(kind);
+        // a list inside, like the a, b in ⟨a, b⟩, is written like a function's arguments
+        let inner: Vec<String> = inner
+            .comma_list()
+            .into_iter()
+            .map(|elem| self.format(elem))
+            .collect();
+        let inner = inner.join(", ");
+        // bars right next to each other would read as a norm: | |x| - 1 |, not ||x| - 1|
+        let pad = if inner.starts_with(['|', '‖']) || inner.ends_with(['|', '‖']) {
+            " "
+        } else {
+            ""
+        };
+        format!(
+            "{}{}{}{}{}",
+            self.format_symbol(&left),
+            pad,
+            inner,
+            pad,
+            self.format_symbol(&right)
+        )
+    }
+
+    fn format_group(&mut self, kind: DelimKind, inner: &ast::AST) -> Self::Output {
+        let (left, right) = delimiter::pair(kind);
+        let inner: Vec<String> = inner
+            .comma_list()
+            .into_iter()
+            .map(|elem| self.format(elem))
+            .collect();
+        format!(
+            "{}{}{}",
+            self.format_symbol(&left),
+            inner.join(", "),
+            self.format_symbol(&right)
+        )
+    }
+
+    fn format_matrix(&mut self, rows: &[Vec<ast::AST>]) -> Self::Output {
+        // each row in its own brackets, as in [[1, 2], [3, 4]]
+        let mut formatted = vec![];
+        for row in rows {
+            let row: Vec<String> = row.iter().map(|elem| self.format(elem)).collect();
+            formatted.push(format!("[{}]", row.join(", ")));
+        }
+        format!("[{}]", formatted.join(", "))
+    }
+
+    fn format_piecewise(&mut self, cases: &[(ast::AST, Option<ast::AST>)]) -> Self::Output {
+        // one case per line, with the conditions lined up and a brace drawn down the left:
+        // ⎧ x   if x ≥ 0
+        // ⎩ −x  otherwise
+        let values: Vec<String> = cases.iter().map(|(value, _)| self.format(value)).collect();
+        let width = values.iter().map(|v| v.chars().count()).max().unwrap_or(0);
+        let middle = (cases.len().saturating_sub(1)) / 2;
+        let mut lines = vec![];
+        for (i, ((_, condition), value)) in cases.iter().zip(&values).enumerate() {
+            let brace = match i {
+                _ if cases.len() == 1 => "{",
+                0 => "⎧",
+                _ if i == cases.len() - 1 => "⎩",
+                _ if i == middle => "⎨",
+                _ => "⎪",
+            };
+            let condition = match condition {
+                Some(condition) => format!("if {}", self.format(condition)),
+                None => "otherwise".to_string(),
+            };
+            let padding = " ".repeat(width - value.chars().count());
+            lines.push(format!("{} {}{}  {}", brace, value, padding, condition));
+        }
+        lines.join("\n")
+    }
 }
 
 #[cfg(test)]
@@ -86,22 +503,19 @@ mod tests {
             Box::new(ast::AST::Function(
                 ast::Symbol::from("f"),
                 vec![
-                    ast::AST::Number("100".to_string()),
+                    ast::AST::Number("100".into()),
                     ast::AST::Sym(ast::Symbol::from("x")),
                 ],
             )),
             Box::new(ast::AST::UnaryExpr(
                 ast::UnaryOp::Generic(ast::Symbol::from("-")),
-                Box::new(ast::AST::Number("12.34".to_string())),
+                Box::new(ast::AST::Number("12.34".into())),
             )),
         );
-        // assert_eq!(
-        //     LatexFormatter {
-        //         decimal_separator: ".".into(),
-        //     }
-        //     .format(&tree),
-        //     "".to_string()
-        // );
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "f(100, x) / -12.34".to_string()
+        );
     }
 
     #[test]
@@ -123,4 +537,233 @@ mod tests {
             r"2 / arccos(μ) + 1".to_string()
         );
     }
+
+    #[test]
+    fn test_roots() {
+        let parser = AsciiParser::default();
+        let tree = parser.parse(&"sqrt(x + 1) + cbrt 8 - root4(y)").unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "√(x + 1) + ∛8 − ∜y"
+        );
+        let tree = parser.parse(&"root(5, x) root(n + 1, 2)").unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "⁵√xroot(n + 1, 2)"
+        );
+    }
+
+    #[test]
+    fn test_big_ops() {
+        let parser = AsciiParser::default();
+        let tree = parser.parse(&"sum_(i=1)^n (i + 1) - int_a^b f dx").unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "∑_(i = 1)^n (i + 1) − ∫ₐᵇ f dx"
+        );
+        let tree = parser.parse(&"sum_i^n i").unwrap();
+        assert_eq!(UnicodeFormatter::default().format(&tree), "∑ᵢⁿ i");
+        let tree = parser.parse(&"2 prod_(k=1)^oo k").unwrap();
+        assert_eq!(UnicodeFormatter::default().format(&tree), "2∏_(k = 1)^∞ k");
+    }
+
+    #[test]
+    fn test_derivatives() {
+        let parser = AsciiParser::default();
+        let tree = parser
+            .parse(&"d^2y/dx^2 + partial f / partial x - d/dx sin x")
+            .unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "d²y/dx² + ∂f/∂x − d/dx sin(x)"
+        );
+        let tree = parser.parse(&"f' + f'' + f''''").unwrap();
+        assert_eq!(UnicodeFormatter::default().format(&tree), "f′ + f″ + f′′′′");
+    }
+
+    #[test]
+    fn test_postfix() {
+        let tree = AsciiParser::default()
+            .parse(&"n! + (n + 1)!! - 90 deg + A^T 5%")
+            .unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "n! + (n + 1)!! − 90° + Aᵀ5%"
+        );
+    }
+
+    #[test]
+    fn test_relations() {
+        let tree = AsciiParser::default().parse(&"x + 1 <= 2 y").unwrap();
+        assert_eq!(UnicodeFormatter::default().format(&tree), "x + 1 ≤ 2y");
+    }
+
+    #[test]
+    fn test_chains() {
+        let tree = AsciiParser::default().parse(&"a = b + 1 = c").unwrap();
+        assert_eq!(UnicodeFormatter::default().format(&tree), "a = b + 1 = c");
+    }
+
+    #[test]
+    fn test_logic() {
+        let tree = AsciiParser::default()
+            .parse(&"a and b or c xor d => e")
+            .unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "a ∧ b ∨ c ⊕ d ⇒ e"
+        );
+        let tree = AsciiParser::default()
+            .parse(&"forall x, exists n > x, n > 0")
+            .unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "∀x, ∃n > x, n > 0"
+        );
+    }
+
+    #[test]
+    fn test_unary_ops() {
+        // prefix operators only get the parentheses their surroundings need, as in the other
+        // formats
+        let parser = AsciiParser::default();
+        let mut unicode = UnicodeFormatter::default();
+        for (input, output) in [
+            ("not p => q", "¬p ⇒ q"),
+            ("not (p and q)", "¬(p ∧ q)"),
+            (
+                "(-b +/- sqrt(b^2 - 4ac)) / (2a)",
+                "(−b ± √(b^2 − 4ac)) / (2a)",
+            ),
+            ("|-x| + (+/- 1)", "|−x| + ±1"),
+            ("2 (-x) (a + b)", "2(−x)(a + b)"),
+        ] {
+            assert_eq!(unicode.format(&parser.parse(&input).unwrap()), output);
+        }
+    }
+
+    #[test]
+    fn test_sets() {
+        let tree = AsciiParser::default()
+            .parse(&"{n in ZZ | n notin NN} cap {}")
+            .unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "{n ∈ ℤ | n ∉ ℕ} ∩ {}"
+        );
+    }
+
+    #[test]
+    fn test_bars() {
+        let tree = AsciiParser::default().parse(&"2|x - 1| + ||v||").unwrap();
+        assert_eq!(UnicodeFormatter::default().format(&tree), "2|x − 1| + ‖v‖");
+    }
+
+    #[test]
+    fn test_delimiters() {
+        let tree = AsciiParser::default()
+            .parse(&"floor(x / 2) y + ceil(y) - <<a, b>>")
+            .unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "⌊x / 2⌋y + ⌈y⌉ − ⟨a, b⟩"
+        );
+    }
+
+    #[test]
+    fn test_applications() {
+        let tree = AsciiParser::default()
+            .parse(&"f(x) + g_1(x, y) - f''((x))")
+            .unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "f(x) + g₁(x, y) − f″(x)"
+        );
+    }
+
+    #[test]
+    fn test_matrices() {
+        let tree = AsciiParser::default()
+            .parse(&"[[1, 2], [3, 4]] [x; y]")
+            .unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "[[1, 2], [3, 4]][[x], [y]]"
+        );
+    }
+
+    #[test]
+    fn test_piecewise() {
+        let tree = AsciiParser::default()
+            .parse(&"f(x) = {x^2 if x > 0; 1 if x = 0; 0 otherwise}")
+            .unwrap();
+        let mut preserve = UnicodeFormatter {
+            grouping: Grouping::Preserve,
+            ..Default::default()
+        };
+        assert_eq!(
+            preserve.format(&tree),
+            "f(x) = ⎧ x^2  if x > 0\n       ⎨ 1    if x = 0\n       ⎩ 0    otherwise"
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        let tree = AsciiParser::default().parse(&"1.2e-3 + 6.02E23 x").unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "1.2 × 10⁻³ + 6.02 × 10²³x"
+        );
+        // numbers next to each other are a product, not one number
+        let tree = AsciiParser::default().parse(&"2 3 x").unwrap();
+        assert_eq!(UnicodeFormatter::default().format(&tree), "2 · 3x");
+        let tree = AsciiParser::default().parse(&"1.2.3").unwrap();
+        assert_eq!(UnicodeFormatter::default().format(&tree), "1.2 · .3");
+    }
+
+    #[test]
+    fn test_mixed_numbers() {
+        let tree = AsciiParser::default().parse(&"1 1/2 + 2 5/7 - ⅓").unwrap();
+        assert_eq!(UnicodeFormatter::default().format(&tree), "1½ + 2⁵⁄₇ − ⅓");
+    }
+
+    #[test]
+    fn test_repeating_decimals() {
+        let tree = AsciiParser::default()
+            .parse(&"0.(3) + 0.1212... - 3.1...")
+            .unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "0.3\u{305} + 0.1\u{305}2\u{305} − 3.1…"
+        );
+    }
+
+    #[test]
+    fn test_locale() {
+        let tree = AsciiParser::default()
+            .parse(&"31415.92654 - 2.5e10")
+            .unwrap();
+        let mut grouped = UnicodeFormatter {
+            numbers: NumberStyle {
+                decimal_comma: true,
+                group_digits: true,
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            grouped.format(&tree),
+            "31\u{2009}415,926\u{2009}54 − 2,5 × 10¹⁰"
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let tree = AsciiParser::default()
+            .parse(&"lim_(x->0) sin(x)/x - lim_(t -> 1^-) (t + 1)")
+            .unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "lim_{x→0} sin(x) / x − lim_{t→1⁻} (t + 1)"
+        );
+    }
 }
@@ -1,10 +1,12 @@
+// the symbol table is one big lazy_static, which takes more macro recursion than the default
+#![recursion_limit = "256"]
+
 use formatter::Formatter;
 use parsers::ASTParser;
 
 #[macro_use]
 extern crate lazy_static;
 
-#[macro_use]
 extern crate nom;
 
 pub mod ast;
@@ -33,8 +35,14 @@ mod tests {
     use super::*;
 
     #[test]
-    fn it_works() {
-        let result = 2 + 2;
-        assert_eq!(result, 4);
+    fn test_conversions() {
+        assert_eq!(
+            texify("2 / (sin mu + 1)"),
+            Some(r"\frac{ 2 }{ \sin\left(\mu\right) + 1 }".to_string())
+        );
+        assert_eq!(
+            unicodeify("2 / (sin mu + 1)"),
+            Some("2 / (sin(μ) + 1)".to_string())
+        );
     }
 }
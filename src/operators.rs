@@ -35,29 +35,138 @@ impl Op {
     }
 }
 
+/// Finds the operator in the list with the longest representation that matches the front of the
+/// input, returning it along with the matched representation. Taking the longest match means that
+/// `+/-` is read as ±, not as a + followed by garbage. Representations ending in a letter have to
+/// end a word, so `x^Ty` is a power and not a transpose.
+pub fn longest_match<'a>(ops: &'a [Op], input: &str) -> Option<(&'a Op, &'a str)> {
+    ops.iter()
+        .flat_map(|op| {
+            op.sym
+                .reprs()
+                .into_iter()
+                .filter(|repr| {
+                    !repr.is_empty()
+                        && input.starts_with(repr)
+                        && !(repr.ends_with(char::is_alphabetic)
+                            && input[repr.len()..].starts_with(char::is_alphanumeric))
+                })
+                .map(move |repr| (op, repr))
+        })
+        .max_by_key(|(_, repr)| repr.len())
+}
+
+/// The operators that can come next in the input. Where an operand is expected, only unary
+/// operators make sense: otherwise, the next operator is binary or postfix.
+pub fn next_ops(expects_operand: bool) -> Vec<Op> {
+    if expects_operand {
+        UNARY_OPS.clone()
+    } else {
+        let mut ops = BINARY_OPS.clone();
+        ops.extend_from_slice(&POSTFIX_OPS);
+        ops
+    }
+}
+
 lazy_static! {
     // Unary operators: these take precedence over binary operators and can't bind things to the
     // left of them.
     pub static ref UNARY_PLUS: Op = Op::new(&symbols::PLUS, None, Some(1));
     pub static ref UNARY_MINUS: Op = Op::new(&symbols::MINUS, None, Some(1));
     pub static ref UNARY_PM: Op = Op::new(&symbols::PM, None, Some(1));
+    // Logical not takes a whole relation, but not a connective: not a = b is not (a = b), and
+    // not a and b is (not a) and b.
+    pub static ref NOT: Op = Op::new(&symbols::NOT, None, Some(12));
+
+    // Postfix operators: these can't bind anything to the right of them.
+
+    // Primes, as in f'. They bind a little looser than subscripts, so x_1' is (x_1)', but tighter
+    // than anything else: x^2' is x^(2').
+    pub static ref PRIME: Op = Op::new(&symbols::PRIME, Some(1), None);
+    // The rest work the same way: -n! is -(n!), and 2 n! is 2 (n!).
+    pub static ref FACTORIAL: Op = Op::new(&symbols::FACTORIAL, Some(1), None);
+    pub static ref DOUBLE_FACTORIAL: Op = Op::new(&symbols::DOUBLE_FACTORIAL, Some(1), None);
+    pub static ref PERCENT: Op = Op::new(&symbols::PERCENT, Some(1), None);
+    pub static ref DEGREE: Op = Op::new(&symbols::DEGREE, Some(1), None);
+    pub static ref TRANSPOSE: Op = Op::new(&symbols::TRANSPOSE, Some(1), None);
 
     // Binary operators. We give the right sides higher precedence when the operator is associative
     // so they associate rightwards: `a + b + c` is parsed as `a + (b + c)`.
 
     // unlike the others, this one needs right precedence: 2 ^ 3 ^ 4 = 2 ^ (3 ^ 4) and not the other
     // way round!
+    // subscripts bind tighter than anything else, so -x_1 is -(x_1) and x_1^2 is (x_1)^2
+    pub static ref SUBSCRIPT: Op = Op::new(&symbols::SUBSCRIPT, Some(1), Some(0));
     pub static ref POWER: Op = Op::new(&symbols::POWER, Some(4), Some(3));
     pub static ref MULT: Op = Op::new(&symbols::MULT, Some(6), Some(5));
     pub static ref DIV: Op = Op::new(&symbols::DIV, Some(6), Some(5));
     pub static ref ADD: Op = Op::new(&symbols::PLUS, Some(7), Some(8));
     pub static ref SUB: Op = Op::new(&symbols::MINUS, Some(7), Some(8));
     pub static ref PM: Op = Op::new(&symbols::PM, Some(7), Some(8));
+    // Set operations work like their arithmetic counterparts: intersection like multiplication, and
+    // union and difference like addition and subtraction.
+    pub static ref INTERSECTION: Op = Op::new(&symbols::INTERSECTION, Some(6), Some(5));
+    pub static ref UNION: Op = Op::new(&symbols::UNION, Some(7), Some(8));
+    pub static ref SETMINUS: Op = Op::new(&symbols::SETMINUS, Some(7), Some(8));
+    // Juxtaposition, as in 2 x, is implicit: it never shows up in the input, but parsing inserts it
+    // between operands that are next to each other. By default it works just like multiplication,
+    // but it can instead bind tighter than multiplication and division while staying looser than
+    // powers, so 1/2 x is 1/(2 x) and 2 x^2 is still 2 (x^2).
+    pub static ref CONCAT: Op = Op::new(&symbols::CONCAT, Some(6), Some(5));
+    pub static ref TIGHT_CONCAT: Op = Op::new(&symbols::CONCAT, Some(5), Some(4));
+
+    // Big operators like sums take everything after them that binds at least as tightly as
+    // multiplication: sum_i 2 i^2 + 1 is (sum_i 2 i^2) + 1. They're like unary operators in that
+    // they don't take anything on the left.
+    pub static ref BIG_OP: Op = Op::new(&symbols::SUM, None, Some(6));
+
+    // The arrow in limits like lim_(x->0). Anything arithmetic on either side goes inside it.
+    pub static ref TO: Op = Op::new(&symbols::TO, Some(9), Some(9));
+
+    // Relations compare whole arithmetic expressions: x + 1 <= 2 y is (x + 1) <= (2 y).
+    pub static ref EQ: Op = Op::new(&symbols::EQ, Some(10), Some(11));
+    pub static ref NEQ: Op = Op::new(&symbols::NEQ, Some(10), Some(11));
+    pub static ref LT: Op = Op::new(&symbols::LT, Some(10), Some(11));
+    pub static ref GT: Op = Op::new(&symbols::GT, Some(10), Some(11));
+    pub static ref LE: Op = Op::new(&symbols::LE, Some(10), Some(11));
+    pub static ref GE: Op = Op::new(&symbols::GE, Some(10), Some(11));
+    pub static ref APPROX: Op = Op::new(&symbols::APPROX, Some(10), Some(11));
+    pub static ref SYM: Op = Op::new(&symbols::SYM, Some(10), Some(11));
+    // so do the set relations: x in A cup B is x in (A cup B)
+    pub static ref ELEM: Op = Op::new(&symbols::ELEM, Some(10), Some(11));
+    pub static ref NOT_ELEM: Op = Op::new(&symbols::NOT_ELEM, Some(10), Some(11));
+    pub static ref SUBSET: Op = Op::new(&symbols::SUBSET, Some(10), Some(11));
+    pub static ref PROPER_SUBSET: Op = Op::new(&symbols::PROPER_SUBSET, Some(10), Some(11));
+
+    // Logical connectives join whole statements. They bind in the usual order, from and to if and
+    // only if: a or b and c is a or (b and c), and p and q => r is (p and q) => r.
+    pub static ref AND: Op = Op::new(&symbols::AND, Some(13), Some(14));
+    pub static ref OR: Op = Op::new(&symbols::OR, Some(15), Some(16));
+    pub static ref XOR: Op = Op::new(&symbols::XOR, Some(15), Some(16));
+    pub static ref IMPLIES: Op = Op::new(&symbols::IMPLIES, Some(17), Some(18));
+    pub static ref IFF: Op = Op::new(&symbols::IFF, Some(19), Some(20));
+
+    // Quantifiers like forall x, take the whole statement after them, connectives and all: forall
+    // x, p => q is forall x, (p => q). Like big operators, they don't take anything on the left.
+    pub static ref QUANTIFIER: Op = Op::new(&symbols::FORALL, None, Some(20));
+
+    // The bar in set-builder notation, as in {x in A | x > 0}, separates whole statements. Sets are
+    // split at it before parsing, so this only matters outside of them.
+    pub static ref MID: Op = Op::new(&symbols::MID, Some(25), Some(26));
 
     // Comma is an operator as a hacky way of allowing expressions like max(1 + 2, 3 + 4). It should
     // be the weakest operator, as the example shows: no matter what operator is used in place +,
     // the postfix version should be 1 2 + 3 4 + , max
-    pub static ref COMMA: Op = Op::new(&symbols::COMMA, Some(10), Some(11));
+    pub static ref COMMA: Op = Op::new(&symbols::COMMA, Some(30), Some(31));
+
+    // The semicolon separates the rows of a matrix, which are themselves separated by commas, so it
+    // binds even more weakly: [1, 2; 3, 4] is [(1, 2); (3, 4)].
+    pub static ref ROW_SEP: Op = Op::new(&symbols::SEMICOLON, Some(35), Some(36));
+
+    // The base of a logarithm like log_2 x. This never comes from the input directly: parsers
+    // write log_b, and that gets rewritten as b LOG before the shunting-yard algorithm runs. Like
+    // functions, it stops at any operator except a subscript: log_2 x_1 is log_2(x_1).
+    pub static ref LOG: Op = Op::new(&symbols::SPECIAL_FUNCS["log"], Some(1), Some(1));
 
     /// The list of unary operators.
     pub static ref UNARY_OPS: Vec<Op> = {
@@ -65,20 +174,65 @@ lazy_static! {
             UNARY_PLUS.clone(),
             UNARY_MINUS.clone(),
             UNARY_PM.clone(),
+            NOT.clone(),
+        ]
+    };
+
+    /// The list of postfix operators.
+    pub static ref POSTFIX_OPS: Vec<Op> = {
+        vec![
+            PRIME.clone(),
+            FACTORIAL.clone(),
+            DOUBLE_FACTORIAL.clone(),
+            PERCENT.clone(),
+            DEGREE.clone(),
+            TRANSPOSE.clone(),
+        ]
+    };
+
+    /// The list of relations, which are binary operators that compare their arguments.
+    pub static ref RELATIONS: Vec<Op> = {
+        vec![
+            EQ.clone(),
+            NEQ.clone(),
+            LT.clone(),
+            GT.clone(),
+            LE.clone(),
+            GE.clone(),
+            APPROX.clone(),
+            SYM.clone(),
+            ELEM.clone(),
+            NOT_ELEM.clone(),
+            SUBSET.clone(),
+            PROPER_SUBSET.clone(),
         ]
     };
 
     /// The list of binary operators.
     pub static ref BINARY_OPS: Vec<Op> = {
-        vec![
+        let mut ops = vec![
+            SUBSCRIPT.clone(),
             POWER.clone(),
             MULT.clone(),
             DIV.clone(),
             ADD.clone(),
             SUB.clone(),
             PM.clone(),
-            COMMA.clone()
-        ]
+            INTERSECTION.clone(),
+            UNION.clone(),
+            SETMINUS.clone(),
+            TO.clone(),
+            AND.clone(),
+            OR.clone(),
+            XOR.clone(),
+            IMPLIES.clone(),
+            IFF.clone(),
+            MID.clone(),
+            COMMA.clone(),
+            ROW_SEP.clone()
+        ];
+        ops.extend_from_slice(RELATIONS.as_slice());
+        ops
     };
 }
 
@@ -90,4 +244,11 @@ mod tests {
     fn test_pm() {
         assert_eq!(PM.match_front("pm 2"), Some("pm"));
     }
+
+    #[test]
+    fn test_longest_match() {
+        assert_eq!(longest_match(&BINARY_OPS, "+/- 2"), Some((&*PM, "+/-")));
+        assert_eq!(longest_match(&BINARY_OPS, "+ 2"), Some((&*ADD, "+")));
+        assert_eq!(longest_match(&BINARY_OPS, "x + 2"), None);
+    }
 }
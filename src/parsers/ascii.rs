@@ -1,11 +1,15 @@
 //! Parser for plaintext math.
 
 use std::collections::VecDeque;
+use std::iter::Peekable;
 
-use crate::ast::{BinaryOp, Fixity, Symbol, SymbolBinaryOp, UnaryOp, AST};
-use crate::delimiter::{self, DelimDir, Delimiter};
+use crate::ast::{
+    BinaryOp, DerivativeKind, Direction, Fixity, Number, Symbol, SymbolBinaryOp, UnaryOp, AST,
+};
+use crate::delimiter::{self, DelimDir, DelimKind, Delimiter};
 use crate::operators::Op;
-use crate::parsers::token::Token;
+use crate::parsers::token::{expects_operand, Prefix, Token, CASE_WORDS};
+use crate::symbols;
 
 use super::token::Tokenizer;
 
@@ -18,6 +22,10 @@ pub enum ParseError {
     MissingOperands,
     /// Indicates an empty expression.
     EmptyExpr,
+    /// Indicates markup, like the XML in MathML, that isn't well-formed.
+    InvalidMarkup,
+    /// Indicates a matrix with rows of different lengths.
+    RaggedMatrix,
 }
 
 /// Parses the list of tokens into postfix.
@@ -32,12 +40,15 @@ pub fn parse_into_postfix(inputs: Vec<Token>) -> Result<VecDeque<Token>, ParseEr
     // use as a queue
     let mut output = VecDeque::new();
 
+    // for each left delimiter on the operator stack, how long the output was when it was pushed
+    let mut group_starts = vec![];
+
     for token in inputs.into_iter() {
         // println!("Before token {:#?}", token.clone());
         // println!("Operators: {:#?}", operators.clone());
         // println!("Output: {:#?}", output.clone());
         match token {
-            Token::Operand(_) => output.push_back(token),
+            Token::Operand(_) | Token::Expr(_) => output.push_back(token),
             Token::Operator(Op {
                 sym: ref _sym1,
                 l_prec: l_prec1,
@@ -60,11 +71,12 @@ pub fn parse_into_postfix(inputs: Vec<Token>) -> Result<VecDeque<Token>, ParseEr
 
                         // this < could be nonstrict, and nothing should change: if two precedences
                         // are equal, that should mean that they're completely interchangeable.
-                        Token::Operator(Op {
-                            sym: _sym,
-                            l_prec: _l_prec,
-                            r_prec,
-                        }) => {
+                        Token::Operator(_) | Token::Prefix(_) => {
+                            let r_prec = &match op2 {
+                                Token::Operator(op) => op.r_prec,
+                                Token::Prefix(prefix) => prefix.r_prec(),
+                                _ => unreachable!(),
+                            };
                             let does_bind = match (l_prec1, r_prec) {
                                 // If both exist, see if rp is lower, meaning more precedent
                                 (Some(lp), Some(rp)) => rp < &lp,
@@ -89,6 +101,16 @@ pub fn parse_into_postfix(inputs: Vec<Token>) -> Result<VecDeque<Token>, ParseEr
                             }
                         }
                         Token::Function(_) => {
+                            // subscripts and postfix operators are part of what they're attached
+                            // to, so they're the exception: sin x_1 is sin(x_1), and sin x' is
+                            // sin(x')
+                            if let Token::Operator(op) = &token {
+                                if op == &*crate::operators::SUBSCRIPT
+                                    || crate::operators::POSTFIX_OPS.contains(op)
+                                {
+                                    break;
+                                }
+                            }
                             // functions never bind through operators without parentheses: sin 2 + x
                             // should convert to 2 sin x +, because if people mean sin (2 + x) they
                             // should write it with parentheses
@@ -103,9 +125,12 @@ pub fn parse_into_postfix(inputs: Vec<Token>) -> Result<VecDeque<Token>, ParseEr
                 }
                 operators.push_front(token);
             }
-            Token::Function(_) => operators.push_front(token),
+            Token::Function(_) | Token::Prefix(_) => operators.push_front(token),
             Token::Delim(Delimiter { dir, kind: lkind }) => match dir {
-                DelimDir::Left => operators.push_front(token),
+                DelimDir::Left => {
+                    group_starts.push(output.len());
+                    operators.push_front(token);
+                }
                 DelimDir::Right => {
                     while let Some(op2) = operators.front() {
                         if let Token::Delim(Delimiter {
@@ -117,10 +142,19 @@ pub fn parse_into_postfix(inputs: Vec<Token>) -> Result<VecDeque<Token>, ParseEr
                                 // found matching pair
                                 // get rid of left paren, it did its duty
                                 operators.pop_front();
+                                let start = group_starts.pop().unwrap_or_default();
                                 // if function, pop onto output
                                 if let Some(Token::Function(_)) = operators.front() {
                                     output.push_back(operators.pop_front().unwrap())
+                                } else if matches!(lkind, DelimKind::Paren | DelimKind::Bracket)
+                                    && output.len() > start
+                                {
+                                    // otherwise, the pair groups what's inside it, which the right
+                                    // delimiter marks in the output
+                                    output.push_back(token);
                                 }
+                                // a right delimiter only closes its own pair
+                                break;
                             } else {
                                 // something like (1 + [2 + 3)] happened and parens are mismatched
                                 return Err(ParseError::MismatchedParentheses);
@@ -138,17 +172,22 @@ pub fn parse_into_postfix(inputs: Vec<Token>) -> Result<VecDeque<Token>, ParseEr
         }
     }
     output.append(&mut operators);
-    return Ok(output);
+    Ok(output)
 }
 
 /// Given an AST, unpacks all outer , operators into a list.
 fn comma_sep_to_list(tree: AST) -> Vec<AST> {
+    sep_to_list(tree, &symbols::COMMA)
+}
+
+/// Given an AST, unpacks all outer operators with the given symbol into a list.
+fn sep_to_list(tree: AST, sep: &Symbol) -> Vec<AST> {
     match tree {
         AST::BinaryExpr(BinaryOp::Generic(SymbolBinaryOp { op, .. }), arg1, arg2)
-            if op.sym == crate::symbols::COMMA.clone() =>
+            if &op.sym == sep =>
         {
-            let mut args1 = comma_sep_to_list(*arg1);
-            let mut args2 = comma_sep_to_list(*arg2);
+            let mut args1 = sep_to_list(*arg1, sep);
+            let mut args2 = sep_to_list(*arg2, sep);
             args1.append(&mut args2);
             args1
         }
@@ -158,6 +197,86 @@ fn comma_sep_to_list(tree: AST) -> Vec<AST> {
     }
 }
 
+/// Makes a matrix out of its rows, each a list separated by commas. Returns None if the rows don't
+/// all have the same length.
+fn rows_to_matrix(rows: Vec<AST>) -> Option<AST> {
+    let rows: Vec<Vec<AST>> = rows.into_iter().map(comma_sep_to_list).collect();
+    let cols = rows.first()?.len();
+    rows.iter()
+        .all(|row| row.len() == cols)
+        .then_some(AST::Matrix(rows))
+}
+
+/// Reads what's inside a pair of brackets as a matrix, if it is one: either rows separated by
+/// semicolons, like `[1, 2; 3, 4]` or the vector `[1; 2; 3]`, or a list of rows that are each in
+/// brackets, like `[[1, 2], [3, 4]]`. A single list in brackets, like `[0, 1]`, isn't a matrix.
+fn bracket_matrix(inner: &AST) -> Option<AST> {
+    let rows = sep_to_list(inner.clone(), &symbols::SEMICOLON);
+    if rows.len() > 1 {
+        return rows_to_matrix(rows);
+    }
+    let rows = comma_sep_to_list(inner.clone())
+        .into_iter()
+        .map(|row| match row {
+            AST::Group(DelimKind::Bracket, row) => Some(*row),
+            _ => None,
+        })
+        .collect::<Option<Vec<AST>>>()?;
+    rows_to_matrix(rows)
+}
+
+/// Makes a tree for a function applied to some arguments. Roots are written like functions, as in
+/// `sqrt(x)` and `root(3, x)`, but they become roots in the tree.
+fn function_to_ast(func: Symbol, mut args: Vec<AST>) -> AST {
+    let index = if func == *symbols::SQRT {
+        Some("2")
+    } else if func == *symbols::CBRT {
+        Some("3")
+    } else if func == *symbols::FOURTH_ROOT {
+        Some("4")
+    } else {
+        None
+    };
+    match (index, args.len()) {
+        (Some(index), 1) => AST::BinaryExpr(
+            BinaryOp::Root,
+            Box::new(AST::Number(Number::from(index))),
+            Box::new(args.remove(0)),
+        ),
+        (None, 2) if func == *symbols::ROOT => {
+            let radicand = args.remove(1);
+            let index = args.remove(0);
+            AST::BinaryExpr(BinaryOp::Root, Box::new(index), Box::new(radicand))
+        }
+        _ => AST::Function(func, args),
+    }
+}
+
+/// Makes a relation between two expressions. Relations associate to the right, so in `0 < x <= 1`
+/// the right side is already `x <= 1`: instead of nesting, that becomes a single chain.
+fn chain_relation(op: Op, left: AST, right: AST) -> AST {
+    match right {
+        AST::BinaryExpr(BinaryOp::Generic(SymbolBinaryOp { op: op2, .. }), middle, last)
+            if crate::operators::RELATIONS.contains(&op2) =>
+        {
+            AST::Chain(Box::new(left), vec![(op, *middle), (op2, *last)])
+        }
+        AST::Chain(middle, rest) => {
+            let mut chain = vec![(op, *middle)];
+            chain.extend(rest);
+            AST::Chain(Box::new(left), chain)
+        }
+        right => AST::BinaryExpr(
+            BinaryOp::Generic(SymbolBinaryOp {
+                op,
+                fixity: Fixity::Infix,
+            }),
+            Box::new(left),
+            Box::new(right),
+        ),
+    }
+}
+
 /// Turns a postfix-ordered list of tokens into an AST.
 pub fn parse_into_tree(tokens: VecDeque<Token>) -> Result<AST, ParseError> {
     let mut exprs = VecDeque::new();
@@ -165,10 +284,25 @@ pub fn parse_into_tree(tokens: VecDeque<Token>) -> Result<AST, ParseError> {
     for token in tokens.into_iter() {
         match token {
             Token::Operand(sym) => exprs.push_front(AST::Sym(sym)),
+            Token::Expr(tree) => exprs.push_front(tree),
             Token::Operator(op) => {
                 // TODO integrate this into type system so it isn't hacky, by adding arity to
                 // operators themselves
-                if crate::operators::UNARY_OPS.contains(&op) {
+                if op == *crate::operators::PRIME {
+                    // f'' is one expression with two primes, not the prime of a prime
+                    let new_expr = match exprs.pop_front() {
+                        Some(AST::Prime(base, count)) => AST::Prime(base, count + 1),
+                        Some(tree) => AST::Prime(Box::new(tree), 1),
+                        None => return Err(ParseError::MissingOperands),
+                    };
+                    exprs.push_front(new_expr);
+                } else if crate::operators::POSTFIX_OPS.contains(&op) {
+                    let new_expr = match exprs.pop_front() {
+                        Some(tree) => AST::UnaryExpr(UnaryOp::Postfix(op.sym), Box::new(tree)),
+                        None => return Err(ParseError::MissingOperands),
+                    };
+                    exprs.push_front(new_expr);
+                } else if crate::operators::UNARY_OPS.contains(&op) {
                     let new_expr = match exprs.pop_front() {
                         Some(tree) => AST::UnaryExpr(UnaryOp::Generic(op.sym), Box::new(tree)),
                         None => return Err(ParseError::MissingOperands),
@@ -178,10 +312,36 @@ pub fn parse_into_tree(tokens: VecDeque<Token>) -> Result<AST, ParseError> {
                     let new_expr = match (exprs.pop_front(), exprs.pop_front()) {
                         (Some(arg2), Some(arg1)) => {
                             // special-case special binary operations
+                            // the parentheses around scripts, fractions and the arguments of a
+                            // log only mark where they start and end, like braces in LaTeX
                             if op == crate::operators::POWER.clone() {
-                                AST::BinaryExpr(BinaryOp::Power, Box::new(arg1), Box::new(arg2))
+                                AST::BinaryExpr(
+                                    BinaryOp::Power,
+                                    Box::new(arg1),
+                                    Box::new(arg2.ungroup_parens()),
+                                )
+                            } else if op == crate::operators::SUBSCRIPT.clone() {
+                                AST::BinaryExpr(
+                                    BinaryOp::Subscript,
+                                    Box::new(arg1),
+                                    Box::new(arg2.ungroup_parens()),
+                                )
+                            } else if op.sym == *symbols::CONCAT {
+                                AST::BinaryExpr(BinaryOp::Concat, Box::new(arg1), Box::new(arg2))
+                            } else if op == crate::operators::LOG.clone() {
+                                AST::BinaryExpr(
+                                    BinaryOp::Log,
+                                    Box::new(arg1.ungroup_parens()),
+                                    Box::new(arg2.ungroup_parens()),
+                                )
                             } else if op == crate::operators::DIV.clone() {
-                                AST::BinaryExpr(BinaryOp::Frac, Box::new(arg1), Box::new(arg2))
+                                AST::BinaryExpr(
+                                    BinaryOp::Frac,
+                                    Box::new(arg1.ungroup_parens()),
+                                    Box::new(arg2.ungroup_parens()),
+                                )
+                            } else if crate::operators::RELATIONS.contains(&op) {
+                                chain_relation(op, arg1, arg2)
                             } else {
                                 AST::BinaryExpr(
                                     BinaryOp::Generic(SymbolBinaryOp {
@@ -198,12 +358,42 @@ pub fn parse_into_tree(tokens: VecDeque<Token>) -> Result<AST, ParseError> {
                     exprs.push_front(new_expr);
                 }
             }
+            Token::Function(func) if func == *symbols::MATRIX => match exprs.pop_front() {
+                Some(tree) => {
+                    let rows = sep_to_list(tree.ungroup_parens(), &symbols::SEMICOLON);
+                    exprs.push_front(rows_to_matrix(rows).ok_or(ParseError::RaggedMatrix)?)
+                }
+                None => return Err(ParseError::MissingOperands),
+            },
             Token::Function(func) => match exprs.pop_front() {
-                Some(tree) => exprs.push_front(AST::Function(func, comma_sep_to_list(tree))),
+                // the parentheses around the arguments belong to the function
+                Some(tree) => exprs.push_front(function_to_ast(
+                    func,
+                    comma_sep_to_list(tree.ungroup_parens()),
+                )),
                 None => return Err(ParseError::MissingOperands),
             },
-            // if there's a delimiter here, it must be a left delimiter that never got cleaned up by
-            // its associated right pair, so parens are mismatched
+            Token::Prefix(prefix) => match exprs.pop_front() {
+                Some(body) => exprs.push_front(prefix.apply(body)),
+                None => return Err(ParseError::MissingOperands),
+            },
+            // a right delimiter groups what came before it, unless the pair makes it a matrix.
+            // The delimiters around a matrix are part of how it's written, so they go too.
+            Token::Delim(Delimiter {
+                dir: DelimDir::Right,
+                kind,
+            }) => match exprs.pop_front() {
+                Some(matrix @ AST::Matrix(_)) => exprs.push_front(matrix),
+                Some(tree) => exprs.push_front(match kind {
+                    DelimKind::Bracket => {
+                        bracket_matrix(&tree).unwrap_or_else(|| AST::Group(kind, Box::new(tree)))
+                    }
+                    _ => AST::Group(kind, Box::new(tree)),
+                }),
+                None => return Err(ParseError::MissingOperands),
+            },
+            // if there's a left delimiter here, it never got cleaned up by its associated right
+            // pair, so parens are mismatched
             Token::Delim(_) => return Err(ParseError::MismatchedParentheses),
             Token::End => {
                 break;
@@ -223,11 +413,599 @@ pub fn parse_into_tree(tokens: VecDeque<Token>) -> Result<AST, ParseError> {
     }
 }
 
+/// Takes the tokens for a script, like the 2 in `log_2`: either a single operand or everything up
+/// to a matching right delimiter.
+fn take_script(tokens: &mut impl Iterator<Item = Token>) -> Result<Vec<Token>, ParseError> {
+    let mut script = vec![];
+    let mut depth = 0;
+    loop {
+        match tokens.next() {
+            Some(Token::End) | None => return Err(ParseError::MissingOperands),
+            Some(Token::Delim(delim)) => {
+                depth += if delim.dir == DelimDir::Left { 1 } else { -1 };
+                script.push(Token::Delim(delim));
+            }
+            Some(token) => script.push(token),
+        }
+        if depth == 0 {
+            return Ok(script);
+        }
+    }
+}
+
+/// Takes the binding after a quantifier: everything up to the comma that ends it, like the `x > 0`
+/// in `forall x > 0, x^2 > 0`, or a single operand if there's no comma, as in `exists x P(x)`.
+/// Returns None if there's nothing after the quantifier.
+fn take_binding(
+    tokens: &mut Peekable<impl Iterator<Item = Token> + Clone>,
+) -> Result<Option<Vec<Token>>, ParseError> {
+    let comma = Token::Operator(crate::operators::COMMA.clone());
+    let mut depth = 0;
+    let mut len = None;
+    for (i, token) in tokens.clone().enumerate() {
+        match token {
+            Token::Delim(delim) if delim.dir == DelimDir::Left => depth += 1,
+            Token::Delim(_) if depth == 0 => break,
+            Token::Delim(_) => depth -= 1,
+            Token::End => break,
+            token if depth == 0 && token == comma => {
+                len = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+    match len {
+        Some(0) => Err(ParseError::MissingOperands),
+        Some(len) => {
+            let binding = tokens.take(len).collect();
+            // the comma is only there to end the binding
+            tokens.next();
+            Ok(Some(binding))
+        }
+        None if matches!(tokens.peek(), Some(Token::End) | None) => Ok(None),
+        None => take_script(tokens).map(Some),
+    }
+}
+
+/// Takes the direction off the end of the subscript of a one-sided limit, like the `^+` in
+/// `lim_(x->0^+)`. It's a bit of a special case: `0^+` isn't a valid expression on its own, because
+/// the `+` is missing an operand.
+fn split_direction(bound: &mut Vec<Token>) -> Option<Direction> {
+    let start = bound
+        .iter()
+        .rposition(|token| token == &Token::Operator(crate::operators::POWER.clone()))?;
+    // superscripts in LaTeX and Unicode come in groups
+    let grouped = bound.get(start + 1) == Some(&Token::Delim(delimiter::LGROUP));
+    let sign = if grouped { start + 2 } else { start + 1 };
+    let direction = match bound.get(sign) {
+        Some(Token::Operator(op)) if op == &*crate::operators::UNARY_PLUS => Direction::Above,
+        Some(Token::Operator(op)) if op == &*crate::operators::UNARY_MINUS => Direction::Below,
+        _ => return None,
+    };
+    let end = if grouped {
+        if bound.get(sign + 1) != Some(&Token::Delim(delimiter::RGROUP)) {
+            return None;
+        }
+        sign + 2
+    } else {
+        sign + 1
+    };
+    bound.drain(start..end);
+    Some(direction)
+}
+
+/// Attaches scripts to the tokens that take them as arguments instead of as subscripts or powers.
+/// Logarithms with a subscripted base, like `log_2 x`, become binary: `log _ 2 x` becomes
+/// `2 LOG x`. Big operators, like `sum_(i=1)^n`, get their bounds, in either order.
+fn attach_scripts(
+    tokens: Vec<Token>,
+    implicit: ImplicitMultiplication,
+) -> Result<Vec<Token>, ParseError> {
+    let log = Token::Function(crate::symbols::SPECIAL_FUNCS["log"].clone());
+    let subscript = Token::Operator(crate::operators::SUBSCRIPT.clone());
+    let power = Token::Operator(crate::operators::POWER.clone());
+    let mut output = vec![];
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Function(_) if token == log && tokens.peek() == Some(&subscript) => {
+                tokens.next();
+                output.append(&mut take_script(&mut tokens)?);
+                output.push(Token::Operator(crate::operators::LOG.clone()));
+            }
+            Token::Prefix(Prefix::BigOp(op, None, None))
+                if op == *symbols::LIM && tokens.peek() == Some(&subscript) =>
+            {
+                tokens.next();
+                let mut bound = take_script(&mut tokens)?;
+                let direction = split_direction(&mut bound);
+                bound.push(Token::End);
+                output.push(match parse_tokens(bound, implicit)?.ungroup_parens() {
+                    AST::BinaryExpr(BinaryOp::Generic(SymbolBinaryOp { op, .. }), var, target)
+                        if op == *crate::operators::TO =>
+                    {
+                        Token::Prefix(Prefix::Limit(var, target, direction))
+                    }
+                    // something like lim_n a_n, which is a limit without any more information
+                    bound => Token::Prefix(Prefix::BigOp(op, Some(Box::new(bound)), None)),
+                });
+            }
+            Token::Prefix(Prefix::BigOp(op, mut lower, mut upper)) => {
+                while let Some(script) = tokens.next_if(|t| t == &subscript || t == &power) {
+                    let mut bound = take_script(&mut tokens)?;
+                    bound.push(Token::End);
+                    let bound = Some(Box::new(parse_tokens(bound, implicit)?.ungroup_parens()));
+                    if script == subscript {
+                        lower = bound;
+                    } else {
+                        upper = bound;
+                    }
+                }
+                output.push(Token::Prefix(Prefix::BigOp(op, lower, upper)));
+            }
+            Token::Operand(sym) if symbols::QUANTIFIERS.contains(&sym) => {
+                match take_binding(&mut tokens)? {
+                    Some(mut binding) => {
+                        binding.push(Token::End);
+                        let binding = Box::new(parse_tokens(binding, implicit)?);
+                        output.push(Token::Prefix(Prefix::Quantifier(sym, binding)));
+                    }
+                    // a quantifier on its own is just a symbol
+                    None => output.push(Token::Operand(sym)),
+                }
+            }
+            _ => output.push(token),
+        }
+    }
+    Ok(output)
+}
+
+/// Reads a lone d or ∂, the kind of derivative it starts.
+fn derivative_kind(tree: &AST) -> Option<DerivativeKind> {
+    match tree {
+        AST::Sym(sym) if sym == &*symbols::PARTIAL => Some(DerivativeKind::Partial),
+        AST::Sym(sym) if sym.ascii_repr == "d" => Some(DerivativeKind::Ordinary),
+        _ => None,
+    }
+}
+
+/// Reads a symbol with a d written right before a single letter, like dx, returning the letter.
+/// Known symbols and longer words that happen to start with a d, like delta or data, aren't
+/// differentials.
+fn strip_d(tree: &AST) -> Option<AST> {
+    match tree {
+        AST::Sym(sym) if !symbols::ALL_SYMBOLS.contains(sym) => {
+            let name = sym.ascii_repr.strip_prefix('d')?;
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_alphabetic() => Some(AST::Sym(Symbol::from(name))),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Reads the order of a derivative from an exponent, along with anything written right after it:
+/// the 2 in d^2 is order 2, and the 2y in d^2y is order 2 of y.
+fn split_order(tree: &AST) -> Option<(u32, Option<AST>)> {
+    let text = match tree {
+        AST::Sym(sym) => &sym.ascii_repr,
+        AST::Number(Number {
+            mantissa,
+            repeating: None,
+            exponent: None,
+        }) => mantissa,
+        _ => return None,
+    };
+    let digits = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let order = text[..digits].parse().ok()?;
+    let rest = &text[digits..];
+    if rest.is_empty() {
+        Some((order, None))
+    } else if rest.chars().all(char::is_alphabetic) {
+        Some((order, Some(AST::Sym(Symbol::from(rest)))))
+    } else {
+        None
+    }
+}
+
+/// Reads the numerator of a derivative, like `dy`, `d^2 y`, or `∂`, returning its kind, its order,
+/// and what it differentiates, if anything.
+fn derivative_numerator(tree: &AST) -> Option<(DerivativeKind, u32, Option<AST>)> {
+    if let Some(kind) = derivative_kind(tree) {
+        return Some((kind, 1, None));
+    }
+    if let Some(function) = strip_d(tree) {
+        return Some((DerivativeKind::Ordinary, 1, Some(function)));
+    }
+    match tree {
+        AST::BinaryExpr(BinaryOp::Power, d, order) => {
+            let kind = derivative_kind(d)?;
+            let (order, function) = split_order(order)?;
+            Some((kind, order, function))
+        }
+        AST::BinaryExpr(BinaryOp::Concat, d, function) => match derivative_numerator(d)? {
+            (kind, order, None) => Some((kind, order, Some(*function.clone()))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Reads the denominator of a derivative, like `dx`, `dx^2`, or `∂ x`, returning its kind, its
+/// order, and the variable.
+fn derivative_denominator(tree: &AST) -> Option<(DerivativeKind, u32, AST)> {
+    if let Some(var) = strip_d(tree) {
+        return Some((DerivativeKind::Ordinary, 1, var));
+    }
+    match tree {
+        // the exponent binds tighter than the juxtaposition, so d x^2 is d (x^2)
+        AST::BinaryExpr(BinaryOp::Concat, d, var) => {
+            let kind = derivative_kind(d)?;
+            match var.as_ref() {
+                AST::Sym(_) => Some((kind, 1, *var.clone())),
+                AST::BinaryExpr(BinaryOp::Power, var, order) if matches!(**var, AST::Sym(_)) => {
+                    match split_order(order)? {
+                        (order, None) => Some((kind, order, *var.clone())),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        }
+        AST::BinaryExpr(BinaryOp::Power, d_var, order) => {
+            match (derivative_denominator(d_var)?, split_order(order)?) {
+                ((kind, 1, var), (order, None)) => Some((kind, order, var)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Reads a fraction as a derivative, if it is one: `dy/dx` and `partial f / partial x` become
+/// derivatives, and `d/dx` becomes a prefix operator that differentiates what comes after it.
+pub fn derivative_token(numerator: &AST, denominator: &AST) -> Option<Token> {
+    let (kind, order, function) = derivative_numerator(numerator)?;
+    let (den_kind, den_order, var) = derivative_denominator(denominator)?;
+    if kind != den_kind || order != den_order {
+        return None;
+    }
+    let var = Box::new(var);
+    Some(match function {
+        Some(function) => Token::Expr(AST::Derivative(kind, order, Box::new(function), var)),
+        None => Token::Prefix(Prefix::Derivative(kind, order, var)),
+    })
+}
+
+/// Parses a few tokens from the middle of an expression on their own, if they make sense.
+fn parse_run(tokens: &[Token], implicit: ImplicitMultiplication) -> Option<AST> {
+    let mut tokens = tokens.to_vec();
+    tokens.push(Token::End);
+    parse_tokens(tokens, implicit).ok()
+}
+
+/// Finds derivatives written as fractions, like `dy/dx`, `d^2y/dx^2`, or `partial f / partial x`,
+/// and turns each of them into a single token. Fractions bind looser than juxtaposition, so this
+/// has to happen before parsing: `d/dx x^2` shouldn't be `(d/dx) x^2`.
+fn find_derivatives(tokens: Vec<Token>, implicit: ImplicitMultiplication) -> Vec<Token> {
+    let div = Token::Operator(crate::operators::DIV.clone());
+    let power = Token::Operator(crate::operators::POWER.clone());
+    // the tokens that can be part of a derivative's numerator or denominator
+    let in_run = |token: &Token| match token {
+        Token::Operand(_) | Token::Expr(AST::Number(_)) => true,
+        Token::Delim(delim) => delim.kind == delimiter::LGROUP.kind,
+        token => token == &power,
+    };
+    let starts_d = |token: &Token| match token {
+        Token::Operand(sym) => {
+            let tree = AST::Sym(sym.clone());
+            derivative_kind(&tree).is_some() || strip_d(&tree).is_some()
+        }
+        _ => false,
+    };
+
+    let mut output: Vec<Token> = vec![];
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens[i] == div {
+            // the denominator is a d or ∂, maybe separate from the variable, and then maybe an
+            // exponent
+            let after = &tokens[i + 1..];
+            let mut den_len = match after {
+                [Token::Operand(d), Token::Operand(_), ..]
+                    if derivative_kind(&AST::Sym(d.clone())).is_some() =>
+                {
+                    2
+                }
+                [token, ..] if starts_d(token) => 1,
+                _ => 0,
+            };
+            if den_len > 0 && after.get(den_len) == Some(&power) {
+                den_len += 1 + take_script(&mut after[den_len + 1..].iter().cloned())
+                    .map_or(0, |script| script.len());
+            }
+            let denominator = parse_run(&after[..den_len], implicit);
+
+            // the numerator is at the end of what came before, starting at a d or ∂ that isn't in
+            // a script
+            let run_start = output
+                .iter()
+                .rposition(|token| !in_run(token))
+                .map_or(0, |pos| pos + 1);
+            let found = denominator.and_then(|denominator| {
+                (run_start..output.len()).rev().find_map(|start| {
+                    if !starts_d(&output[start]) || (start > 0 && output[start - 1] == power) {
+                        return None;
+                    }
+                    let numerator = parse_run(&output[start..], implicit)?;
+                    Some((start, derivative_token(&numerator, &denominator)?))
+                })
+            });
+            if let Some((start, derivative)) = found {
+                output.truncate(start);
+                output.push(derivative);
+                i += 1 + den_len;
+                continue;
+            }
+        }
+        output.push(tokens[i].clone());
+        i += 1;
+    }
+    output
+}
+
+/// Finds the delimiters that mean something on their own and turns each pair into a single token:
+/// sets written with braces, like `{1, 2}` or `{x in A | x > 0}`, and delimiters that stand for a
+/// function of what's inside, like `|x|`, `||v||`, `floor(x)` or `<<a, b>>`. The bar in set-builder
+/// notation splits the whole set in two, no matter what's around it, so this has to happen before
+/// parsing.
+fn find_fenced(
+    tokens: Vec<Token>,
+    implicit: ImplicitMultiplication,
+) -> Result<Vec<Token>, ParseError> {
+    let mut output = vec![];
+    let mut tokens = tokens.into_iter();
+    while let Some(token) = tokens.next() {
+        let kind = match token {
+            Token::Delim(Delimiter {
+                dir: DelimDir::Left,
+                kind:
+                    kind @ (DelimKind::Brace
+                    | DelimKind::Abs
+                    | DelimKind::Norm
+                    | DelimKind::Floor
+                    | DelimKind::Ceil
+                    | DelimKind::Angle),
+            }) => kind,
+            _ => {
+                output.push(token);
+                continue;
+            }
+        };
+        // everything up to the matching delimiter, which might have pairs of its own
+        let mut inside = vec![];
+        let mut depth = 0;
+        loop {
+            match tokens.next() {
+                Some(Token::Delim(delim)) if delim.kind == kind => {
+                    depth += if delim.dir == DelimDir::Left { 1 } else { -1 };
+                    if depth < 0 {
+                        break;
+                    }
+                    inside.push(Token::Delim(delim));
+                }
+                Some(Token::End) | None => return Err(ParseError::MismatchedParentheses),
+                Some(token) => inside.push(token),
+            }
+        }
+        output.push(Token::Expr(match kind {
+            DelimKind::Brace => match piecewise_to_ast(&inside, implicit)? {
+                Some(piecewise) => piecewise,
+                None => set_to_ast(inside, implicit)?,
+            },
+            kind => {
+                inside.push(Token::End);
+                AST::Fenced(kind, Box::new(parse_tokens(inside, implicit)?))
+            }
+        }));
+    }
+    Ok(output)
+}
+
+/// Whether a token is a word used to give the condition of a piecewise case: `if` or `otherwise`.
+fn is_case_word(token: &Token, word: &str) -> bool {
+    matches!(token, Token::Operand(sym) if sym.ascii_repr == word)
+}
+
+/// Parses the condition after an `if`. The `if` looks like an operand to the tokenizer, so the minus
+/// in `if -1 < x` comes out binary and has to be made unary again.
+fn parse_condition(
+    mut condition: Vec<Token>,
+    implicit: ImplicitMultiplication,
+) -> Result<AST, ParseError> {
+    if let Some(Token::Operator(op)) = condition.first_mut() {
+        if let Some(unary) = crate::operators::UNARY_OPS.iter().find(|u| u.sym == op.sym) {
+            *op = unary.clone();
+        }
+    }
+    parse_tokens(condition, implicit)
+}
+
+/// Reads the condition column of a case in a LaTeX `cases` environment, which can be `if` and a
+/// condition, `otherwise`, or, as it usually is, just the condition: `1 & x > 0`. Returns None for
+/// a case that applies otherwise.
+pub(crate) fn cases_condition(
+    mut condition: Vec<Token>,
+    implicit: ImplicitMultiplication,
+) -> Result<Option<AST>, ParseError> {
+    match condition.as_slice() {
+        [Token::End] => return Ok(None),
+        [word, Token::End] if is_case_word(word, "otherwise") => return Ok(None),
+        [word, ..] if is_case_word(word, "if") => {
+            condition.remove(0);
+        }
+        _ => {}
+    }
+    parse_condition(condition, implicit).map(Some)
+}
+
+/// Makes a piecewise function out of the inside of a brace, like `{x if x >= 0; -x otherwise}`,
+/// if that's what it is: cases split by semicolons, each a value followed by `if` and a condition
+/// or by `otherwise`. A comma between the value and the condition is allowed too, as is the `&`
+/// LaTeX puts there. Returns None for anything else, which should be read as a set.
+fn piecewise_to_ast(
+    inside: &[Token],
+    implicit: ImplicitMultiplication,
+) -> Result<Option<AST>, ParseError> {
+    let row_sep = Token::Operator(crate::operators::ROW_SEP.clone());
+    let comma = Token::Operator(crate::operators::COMMA.clone());
+    // split into cases at the semicolons that aren't inside anything else
+    let mut cases = vec![vec![]];
+    let mut depth = 0;
+    for token in inside {
+        if let Token::Delim(delim) = token {
+            depth += if delim.dir == DelimDir::Left { 1 } else { -1 };
+        }
+        if depth == 0 && token == &row_sep {
+            cases.push(vec![]);
+        } else {
+            cases.last_mut().unwrap().push(token.clone());
+        }
+    }
+    let mut output = vec![];
+    for mut case in cases {
+        let mut depth = 0;
+        let split = case.iter().position(|token| match token {
+            Token::Delim(delim) => {
+                depth += if delim.dir == DelimDir::Left { 1 } else { -1 };
+                false
+            }
+            token => depth == 0 && CASE_WORDS.iter().any(|word| is_case_word(token, word)),
+        });
+        let Some(i) = split else {
+            return Ok(None);
+        };
+        let mut condition = case.split_off(i + 1);
+        let word = case.pop().unwrap();
+        if case.last() == Some(&comma) {
+            case.pop();
+        }
+        let condition = if is_case_word(&word, "if") {
+            condition.push(Token::End);
+            Some(parse_condition(condition, implicit)?)
+        } else if condition.is_empty() {
+            None
+        } else {
+            return Ok(None);
+        };
+        case.push(Token::End);
+        output.push((parse_tokens(case, implicit)?, condition));
+    }
+    Ok(Some(AST::Piecewise(output)))
+}
+
+/// Makes a tree for the inside of a set: either a list of elements, or members and a condition
+/// split by a bar.
+fn set_to_ast(mut inside: Vec<Token>, implicit: ImplicitMultiplication) -> Result<AST, ParseError> {
+    if inside.is_empty() {
+        return Ok(AST::Set(vec![]));
+    }
+    let mid = Token::Operator(crate::operators::MID.clone());
+    let mut depth = 0;
+    let split = inside.iter().position(|token| match token {
+        Token::Delim(delim) => {
+            depth += if delim.dir == DelimDir::Left { 1 } else { -1 };
+            false
+        }
+        token => depth == 0 && token == &mid,
+    });
+    match split {
+        Some(i) => {
+            let mut condition = inside.split_off(i + 1);
+            // get rid of the bar
+            inside.pop();
+            inside.push(Token::End);
+            condition.push(Token::End);
+            Ok(AST::SetBuilder(
+                Box::new(parse_tokens(inside, implicit)?),
+                Box::new(parse_tokens(condition, implicit)?),
+            ))
+        }
+        None => {
+            inside.push(Token::End);
+            Ok(AST::Set(comma_sep_to_list(parse_tokens(inside, implicit)?)))
+        }
+    }
+}
+
+/// Makes juxtaposition explicit, inserting the implicit concatenation operator between any two
+/// tokens that are next to each other with no operator in between: `2 x`, `x_1 y_2`, or
+/// `sin(x) cos(x)`. Because juxtaposition is then an operator like any other, a big operator's
+/// body takes in everything juxtaposed in it by the usual precedence rules, like the `x^2 dx` in
+/// `int x^2 dx`. How tightly that operator binds depends on `implicit`.
+fn insert_concat(tokens: Vec<Token>, implicit: ImplicitMultiplication) -> Vec<Token> {
+    let mut output: Vec<Token> = vec![];
+    for token in tokens.into_iter() {
+        let starts_operand = match &token {
+            Token::Operand(_) | Token::Expr(_) | Token::Function(_) | Token::Prefix(_) => true,
+            Token::Delim(Delimiter { dir, kind: _ }) => dir == &DelimDir::Left,
+            Token::Operator(_) | Token::End => false,
+        };
+        if starts_operand && !output.is_empty() && !expects_operand(output.last()) {
+            output.push(Token::Operator(implicit.op().clone()));
+        }
+        output.push(token);
+    }
+    output
+}
+
+/// Turns a list of tokens into an AST. This is the part of parsing that every input format shares:
+/// once an input has been tokenized, the rest of the process is the same. `implicit` decides how
+/// tightly operands next to each other, like the `2` and `x` in `1/2x`, are bound together.
+pub fn parse_tokens(
+    tokens: Vec<Token>,
+    implicit: ImplicitMultiplication,
+) -> Result<AST, ParseError> {
+    let tokens = attach_scripts(find_fenced(tokens, implicit)?, implicit)?;
+    let tokens = insert_concat(find_derivatives(tokens, implicit), implicit);
+    parse_into_tree(parse_into_postfix(tokens)?)
+}
+
+/// How tightly implicit multiplication, the juxtaposition in `2x` or `a b`, binds. Either way it
+/// binds looser than powers, so `2x^2` is `2 (x^2)`, and both read `a b / c` as `(a b) / c`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImplicitMultiplication {
+    /// Juxtaposition works just like `*`, left to right with `/`, as calculators read it: `1/2x`
+    /// is `(1/2) x`.
+    #[default]
+    LikeMultiplication,
+    /// Juxtaposition binds tighter than `*` and `/`, as many physicists read it: `1/2x` is
+    /// `1/(2x)`.
+    TighterThanDivision,
+}
+
+impl ImplicitMultiplication {
+    /// The operator inserted between operands that are next to each other.
+    pub fn op(self) -> &'static Op {
+        match self {
+            ImplicitMultiplication::LikeMultiplication => &crate::operators::CONCAT,
+            ImplicitMultiplication::TighterThanDivision => &crate::operators::TIGHT_CONCAT,
+        }
+    }
+}
+
 /// A parser for ASCII.
 #[derive(Debug, Clone, Default)]
 pub struct AsciiParser {
-    /// The tokenizer to use.
-    tokenizer: Tokenizer,
+    /// The tokenizer to use, which knows how numbers are written.
+    pub tokenizer: Tokenizer,
+    /// How tightly implicit multiplication binds.
+    pub implicit_multiplication: ImplicitMultiplication,
 }
 
 impl<T> super::ASTParser<T> for AsciiParser
@@ -238,28 +1016,17 @@ where
 
     fn parse(&self, input: &T) -> Result<AST, Self::ParseError> {
         let input = input.to_string();
-        let tokens = self.tokenizer.tokenize(&input);
-        let postfix = parse_into_postfix(tokens)?;
-        dbg!(
-            "{}",
-            postfix
-                .clone()
-                .into_iter()
-                .map(|x| x.to_string())
-                .collect::<Vec<String>>()
-                .join(" ")
-        );
-        dbg!(
-            "{:#?}",
-            parse_into_tree(postfix.clone()).unwrap_or(AST::Sym(Symbol::from("oops")))
-        );
-        parse_into_tree(postfix)
+        parse_tokens(
+            self.tokenizer.tokenize(&input),
+            self.implicit_multiplication,
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::parsers::token::Tokenizer;
+    use crate::parsers::{ASTParser, LatexParser, UnicodeParser};
 
     use super::*;
 
@@ -269,6 +1036,883 @@ mod tests {
     //     assert_eq!(parse_into_postfix(tokens).unwrap(), vec![]);
     // }
 
+    #[test]
+    fn test_right_delimiters() {
+        // a right delimiter closes its own pair and nothing else: the + before (2) is still waiting
+        // for the * after it, and the outer ( still needs its own )
+        let postfix = |input: &str| {
+            parse_into_postfix(Tokenizer::default().tokenize(input))
+                .unwrap()
+                .iter()
+                .map(|token| token.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        assert_eq!(postfix("1 + (2) * 3"), "1 2 ) 3 · +");
+        assert_eq!(postfix("((1 + 2) * 3)"), "1 2 + ) 3 · )");
+        let parser = AsciiParser::default();
+        assert_eq!(
+            parser.parse(&"1 + (2) * 3").unwrap().without_groups(),
+            parser.parse(&"1 + 2 * 3").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unicode() {
+        let parser = UnicodeParser::default();
+        assert_eq!(
+            parser.parse(&"x² + π/2 · √(y₁)").unwrap(),
+            LatexParser::default()
+                .parse(&r"x^{2} + \frac{\pi}{2} \cdot \sqrt{y_1}")
+                .unwrap()
+        );
+        assert_eq!(
+            parser.parse(&"sin⁻¹(x) ÷ 2 − ∛8").unwrap(),
+            parser.parse(&"sin^-1(x) / 2 - cbrt 8").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_subscripts() {
+        let parser = AsciiParser::default();
+        let x_i = AST::BinaryExpr(
+            BinaryOp::Subscript,
+            Box::new(AST::Sym("x".into())),
+            Box::new(AST::Sym("i".into())),
+        );
+        assert_eq!(parser.parse(&"x_i").unwrap(), x_i);
+        assert_eq!(
+            parser.parse(&"x_i^2").unwrap(),
+            AST::BinaryExpr(
+                BinaryOp::Power,
+                Box::new(x_i.clone()),
+                Box::new(AST::Number("2".into()))
+            )
+        );
+        assert_eq!(parser.parse(&"x_{i}").unwrap(), x_i);
+        assert_eq!(parser.parse(&"xᵢ").unwrap(), x_i);
+        assert_eq!(
+            parser.parse(&"-x_(i + 1)").unwrap(),
+            parser.parse(&"-(x_(i + 1))").unwrap().without_groups()
+        );
+        assert_eq!(
+            parser.parse(&"sin x_i").unwrap(),
+            AST::Function(
+                crate::symbols::SPECIAL_FUNCS["sin"].clone(),
+                vec![x_i.clone()]
+            )
+        );
+        assert_eq!(
+            parser.parse(&"log_2 x_i + 1").unwrap(),
+            parser.parse(&"log_(2)(x_i) + 1").unwrap()
+        );
+        assert_eq!(
+            parser.parse(&"x_i y").unwrap(),
+            AST::BinaryExpr(
+                BinaryOp::Concat,
+                Box::new(x_i.clone()),
+                Box::new(AST::Sym("y".into()))
+            )
+        );
+        assert_eq!(
+            parser.parse(&"log_2 x").unwrap(),
+            AST::BinaryExpr(
+                BinaryOp::Log,
+                Box::new(AST::Number("2".into())),
+                Box::new(AST::Sym("x".into()))
+            )
+        );
+    }
+
+    #[test]
+    fn test_roots() {
+        let parser = AsciiParser::default();
+        let root = |index: &str, radicand: &str| {
+            AST::BinaryExpr(
+                BinaryOp::Root,
+                Box::new(AST::Number(index.into())),
+                Box::new(parser.parse(&radicand).unwrap()),
+            )
+        };
+        assert_eq!(parser.parse(&"sqrt(x)").unwrap(), root("2", "x"));
+        assert_eq!(parser.parse(&"sqrt x").unwrap(), root("2", "x"));
+        assert_eq!(parser.parse(&"√x").unwrap(), root("2", "x"));
+        assert_eq!(parser.parse(&"cbrt(8)").unwrap(), root("3", "8"));
+        assert_eq!(parser.parse(&"∜ 16").unwrap(), root("4", "16"));
+        assert_eq!(parser.parse(&"root(3, x)").unwrap(), root("3", "x"));
+        assert_eq!(
+            parser.parse(&"sqrt x + 1").unwrap(),
+            parser.parse(&"(sqrt x) + 1").unwrap().without_groups()
+        );
+    }
+
+    #[test]
+    fn test_big_ops() {
+        let parser = AsciiParser::default();
+        let tree = parser.parse(&"sum_(i=1)^n i^2 + 1").unwrap();
+        assert_eq!(
+            tree,
+            parser
+                .parse(&"(sum_(i=1)^n (i^2)) + 1".to_string())
+                .unwrap()
+                .without_groups()
+        );
+        match tree {
+            AST::BinaryExpr(_, sum, _) => match *sum {
+                AST::BigOp(op, Some(lower), Some(upper), _) => {
+                    assert_eq!(op, symbols::SUM.clone());
+                    assert_eq!(*lower, parser.parse(&"i = 1").unwrap());
+                    assert_eq!(*upper, AST::Sym("n".into()));
+                }
+                other => panic!("not a sum: {:?}", other),
+            },
+            other => panic!("not a sum plus 1: {:?}", other),
+        }
+        // the bounds can come in either order, and the body takes in juxtaposition
+        assert_eq!(
+            parser.parse(&"int^1_0 x^2 dx").unwrap(),
+            AST::BigOp(
+                symbols::INT.clone(),
+                Some(Box::new(AST::Number("0".into()))),
+                Some(Box::new(AST::Number("1".into()))),
+                Box::new(parser.parse(&"x^2 dx").unwrap())
+            )
+        );
+        assert_eq!(
+            parser.parse(&"∏ₖ k").unwrap(),
+            parser.parse(&"prod_k k").unwrap()
+        );
+        assert!(parser.parse(&"sum_i").is_err());
+    }
+
+    #[test]
+    fn test_limits() {
+        let parser = AsciiParser::default();
+        let x = || Box::new(AST::Sym("x".into()));
+        assert_eq!(
+            parser.parse(&"lim_(x->0) sin(x)/x").unwrap(),
+            AST::Limit(
+                x(),
+                Box::new(AST::Number("0".into())),
+                None,
+                Box::new(parser.parse(&"sin(x)/x").unwrap())
+            )
+        );
+        let one_sided = AST::Limit(
+            x(),
+            Box::new(AST::Number("0".into())),
+            Some(Direction::Above),
+            Box::new(parser.parse(&"1/x").unwrap()),
+        );
+        assert_eq!(parser.parse(&"lim_(x -> 0^+) 1/x").unwrap(), one_sided);
+        assert_eq!(parser.parse(&"lim_(x→0⁺) 1/x").unwrap(), one_sided);
+        assert_eq!(
+            LatexParser::default()
+                .parse(&r"\lim_{x \to 0^+} \frac{1}{x}")
+                .unwrap(),
+            one_sided
+        );
+        // the body stops at addition, like with sums
+        assert_eq!(
+            parser.parse(&"lim_(x -> 0^+) 1/x + 1").unwrap(),
+            AST::BinaryExpr(
+                BinaryOp::Generic(SymbolBinaryOp {
+                    op: crate::operators::ADD.clone(),
+                    fixity: Fixity::Infix
+                }),
+                Box::new(one_sided),
+                Box::new(AST::Number("1".into()))
+            )
+        );
+    }
+
+    #[test]
+    fn test_derivatives() {
+        let parser = AsciiParser::default();
+        let x = || Box::new(AST::Sym("x".into()));
+        let f = || Box::new(AST::Sym("f".into()));
+        let y = || Box::new(AST::Sym("y".into()));
+        assert_eq!(
+            parser.parse(&"dy/dx").unwrap(),
+            AST::Derivative(DerivativeKind::Ordinary, 1, y(), x())
+        );
+        assert_eq!(
+            parser.parse(&"d^2y/dx^2").unwrap(),
+            AST::Derivative(DerivativeKind::Ordinary, 2, y(), x())
+        );
+        assert_eq!(
+            parser.parse(&"d²y/dx²").unwrap(),
+            AST::Derivative(DerivativeKind::Ordinary, 2, y(), x())
+        );
+        let partial = AST::Derivative(DerivativeKind::Partial, 1, f(), x());
+        assert_eq!(parser.parse(&"partial f / partial x").unwrap(), partial);
+        assert_eq!(parser.parse(&"∂f/∂x").unwrap(), partial);
+        assert_eq!(
+            LatexParser::default()
+                .parse(&r"\frac{\partial f}{\partial x}")
+                .unwrap(),
+            partial
+        );
+        // d/dx works like a big operator
+        let x_squared = parser.parse(&"x^2").unwrap();
+        let derivative = AST::Derivative(DerivativeKind::Ordinary, 1, Box::new(x_squared), x());
+        assert_eq!(parser.parse(&"d/dx (x^2)").unwrap(), derivative);
+        assert_eq!(parser.parse(&"d/dx x^2").unwrap(), derivative);
+        assert_eq!(
+            LatexParser::default()
+                .parse(&r"\frac{d}{dx} x^{2}")
+                .unwrap(),
+            derivative
+        );
+        // a d that doesn't start a derivative is just a fraction
+        assert_eq!(
+            parser.parse(&"x/dy").unwrap(),
+            AST::BinaryExpr(BinaryOp::Frac, x(), Box::new(AST::Sym("dy".into())))
+        );
+        assert_eq!(
+            parser.parse(&"d^2y/dx").unwrap(),
+            AST::BinaryExpr(
+                BinaryOp::Frac,
+                Box::new(parser.parse(&"d^2y").unwrap()),
+                Box::new(AST::Sym("dx".into()))
+            )
+        );
+        // only a d right before a single letter is a differential, not any word starting with d
+        let word = |name: &str| Box::new(AST::Sym(name.into()));
+        assert_eq!(
+            parser.parse(&"data/days").unwrap(),
+            AST::BinaryExpr(BinaryOp::Frac, word("data"), word("days"))
+        );
+        assert_eq!(
+            parser.parse(&"dog/dad").unwrap(),
+            AST::BinaryExpr(BinaryOp::Frac, word("dog"), word("dad"))
+        );
+        assert_eq!(
+            parser.parse(&"d/delta x").unwrap(),
+            AST::BinaryExpr(
+                BinaryOp::Concat,
+                Box::new(AST::BinaryExpr(
+                    BinaryOp::Frac,
+                    word("d"),
+                    Box::new(parser.parse(&"delta").unwrap())
+                )),
+                x()
+            )
+        );
+    }
+
+    #[test]
+    fn test_primes() {
+        let parser = AsciiParser::default();
+        let f = || Box::new(AST::Sym("f".into()));
+        assert_eq!(parser.parse(&"f'").unwrap(), AST::Prime(f(), 1));
+        assert_eq!(parser.parse(&"f''").unwrap(), AST::Prime(f(), 2));
+        assert_eq!(parser.parse(&"f″").unwrap(), AST::Prime(f(), 2));
+        assert_eq!(
+            parser.parse(&"f'(x)").unwrap(),
+            AST::BinaryExpr(
+                BinaryOp::Concat,
+                Box::new(AST::Prime(f(), 1)),
+                Box::new(AST::Group(DelimKind::Paren, Box::new(AST::Sym("x".into()))))
+            )
+        );
+        // primes bind tighter than everything but subscripts
+        assert_eq!(
+            parser.parse(&"y_1' + 1").unwrap(),
+            parser.parse(&"(y_1)' + 1").unwrap().without_groups()
+        );
+        assert_eq!(
+            parser.parse(&"-f'").unwrap(),
+            AST::UnaryExpr(
+                UnaryOp::Generic(crate::symbols::MINUS.clone()),
+                Box::new(AST::Prime(f(), 1))
+            )
+        );
+        assert_eq!(
+            LatexParser::default().parse(&"f''").unwrap(),
+            AST::Prime(f(), 2)
+        );
+    }
+
+    #[test]
+    fn test_postfix() {
+        let parser = AsciiParser::default();
+        let n = || Box::new(AST::Sym("n".into()));
+        let postfix = |sym: &Symbol, arg| AST::UnaryExpr(UnaryOp::Postfix(sym.clone()), arg);
+        assert_eq!(
+            parser.parse(&"n!").unwrap(),
+            postfix(&crate::symbols::FACTORIAL, n())
+        );
+        assert_eq!(
+            parser.parse(&"n!!").unwrap(),
+            postfix(&crate::symbols::DOUBLE_FACTORIAL, n())
+        );
+        assert_eq!(
+            parser.parse(&"n%").unwrap(),
+            postfix(&crate::symbols::PERCENT, n())
+        );
+        for input in ["n deg", "n degrees", "n°"] {
+            assert_eq!(
+                parser.parse(&input).unwrap(),
+                postfix(&crate::symbols::DEGREE, n())
+            );
+        }
+        for input in ["n^T", "nᵀ"] {
+            assert_eq!(
+                parser.parse(&input).unwrap(),
+                postfix(&crate::symbols::TRANSPOSE, n())
+            );
+        }
+        // T is only a transpose on its own, and deg is only degrees on its own
+        assert_eq!(
+            parser.parse(&"n^Tx").unwrap(),
+            AST::BinaryExpr(BinaryOp::Power, n(), Box::new(AST::Sym("Tx".into())))
+        );
+        assert_eq!(parser.parse(&"ndeg").unwrap(), AST::Sym("ndeg".into()));
+        // postfix operators bind tightly
+        assert_eq!(
+            parser.parse(&"2 n! + 1").unwrap(),
+            parser.parse(&"2 (n!) + 1").unwrap().without_groups()
+        );
+        assert_eq!(
+            parser.parse(&"-n!").unwrap(),
+            AST::UnaryExpr(
+                UnaryOp::Generic(crate::symbols::MINUS.clone()),
+                Box::new(postfix(&crate::symbols::FACTORIAL, n()))
+            )
+        );
+        let latex = LatexParser::default();
+        assert_eq!(
+            latex
+                .parse(&r"n! + 90^{\circ} - A^{\top} \cdot 5\%")
+                .unwrap(),
+            parser.parse(&"n! + 90 deg - A^T * 5%").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_relations() {
+        let parser = AsciiParser::default();
+        let relation = |op: &Op, left: &str, right: &str| {
+            AST::BinaryExpr(
+                BinaryOp::Generic(SymbolBinaryOp {
+                    op: op.clone(),
+                    fixity: Fixity::Infix,
+                }),
+                Box::new(parser.parse(&left).unwrap()),
+                Box::new(parser.parse(&right).unwrap()),
+            )
+        };
+        assert_eq!(
+            parser.parse(&"x + 1 <= 2 y").unwrap(),
+            relation(&crate::operators::LE, "x + 1", "2 y")
+        );
+        assert_eq!(
+            parser.parse(&"x^2 ≠ -1").unwrap(),
+            relation(&crate::operators::NEQ, "x^2", "-1")
+        );
+        // != is a relation, not a factorial
+        assert_eq!(
+            parser.parse(&"n!=1").unwrap(),
+            relation(&crate::operators::NEQ, "n", "1")
+        );
+        assert_eq!(
+            parser.parse(&"n! = 1").unwrap(),
+            relation(&crate::operators::EQ, "n!", "1")
+        );
+        // relations bind tighter than commas
+        assert_eq!(
+            parser.parse(&"max(a < b, c)").unwrap(),
+            AST::Function(
+                crate::symbols::SPECIAL_FUNCS["max"].clone(),
+                vec![
+                    relation(&crate::operators::LT, "a", "b"),
+                    AST::Sym("c".into())
+                ]
+            )
+        );
+        assert_eq!(
+            LatexParser::default()
+                .parse(&r"\frac{1}{x} \geq y \cdot 2")
+                .unwrap(),
+            relation(&crate::operators::GE, "1/x", "y * 2")
+        );
+    }
+
+    #[test]
+    fn test_chains() {
+        let parser = AsciiParser::default();
+        let tree = parser.parse(&"0 < x + 1 <= 1").unwrap();
+        assert_eq!(
+            tree,
+            AST::Chain(
+                Box::new(AST::Number("0".into())),
+                vec![
+                    (
+                        crate::operators::LT.clone(),
+                        parser.parse(&"x + 1").unwrap()
+                    ),
+                    (crate::operators::LE.clone(), AST::Number("1".into())),
+                ]
+            )
+        );
+        // a chain means each of its comparisons holds
+        assert_eq!(
+            tree.comparisons(),
+            vec![
+                parser.parse(&"0 < x + 1").unwrap(),
+                parser.parse(&"x + 1 <= 1").unwrap()
+            ]
+        );
+        assert_eq!(
+            parser.parse(&"a = b = c = d").unwrap().comparisons().len(),
+            3
+        );
+        // a single relation isn't a chain
+        let relation = parser.parse(&"a = b").unwrap();
+        assert!(matches!(relation, AST::BinaryExpr(_, _, _)));
+        assert_eq!(relation.comparisons(), vec![relation.clone()]);
+    }
+
+    #[test]
+    fn test_logic() {
+        let parser = AsciiParser::default();
+        let binary = |op: &Op, left: AST, right: AST| {
+            AST::BinaryExpr(
+                BinaryOp::Generic(SymbolBinaryOp {
+                    op: op.clone(),
+                    fixity: Fixity::Infix,
+                }),
+                Box::new(left),
+                Box::new(right),
+            )
+        };
+        let not = |arg: AST| AST::UnaryExpr(UnaryOp::Generic(symbols::NOT.clone()), Box::new(arg));
+        let sym = |name: &str| AST::Sym(name.into());
+        // and binds tighter than or, which binds tighter than implication
+        assert_eq!(
+            parser.parse(&"a or b and c => d").unwrap(),
+            binary(
+                &crate::operators::IMPLIES,
+                binary(
+                    &crate::operators::OR,
+                    sym("a"),
+                    binary(&crate::operators::AND, sym("b"), sym("c"))
+                ),
+                sym("d")
+            )
+        );
+        assert_eq!(
+            parser.parse(&"a || b && c => d").unwrap(),
+            parser.parse(&"a ∨ b ∧ c ⇒ d").unwrap()
+        );
+        // not takes a whole relation, but stops at connectives
+        assert_eq!(
+            parser.parse(&"not x = 1 and y").unwrap(),
+            binary(
+                &crate::operators::AND,
+                not(parser.parse(&"x = 1").unwrap()),
+                sym("y")
+            )
+        );
+        assert_eq!(parser.parse(&"!p").unwrap(), not(sym("p")));
+        assert_eq!(
+            parser.parse(&"¬p <=> q xor r").unwrap(),
+            binary(
+                &crate::operators::IFF,
+                not(sym("p")),
+                binary(&crate::operators::XOR, sym("q"), sym("r"))
+            )
+        );
+        // words inside other words are left alone
+        assert_eq!(parser.parse(&"nor").unwrap(), sym("nor"));
+        assert_eq!(
+            LatexParser::default()
+                .parse(&r"\neg p \land q \implies r")
+                .unwrap(),
+            parser.parse(&"not p and q => r").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_quantifiers() {
+        let parser = AsciiParser::default();
+        // the binding goes up to the comma, and the body takes the rest
+        assert_eq!(
+            parser.parse(&"forall x > 0, x^2 > 0 and x != 0").unwrap(),
+            AST::Quantifier(
+                symbols::FORALL.clone(),
+                Box::new(parser.parse(&"x > 0").unwrap()),
+                Box::new(parser.parse(&"x^2 > 0 and x != 0").unwrap())
+            )
+        );
+        assert_eq!(
+            parser.parse(&"∀x, ∃y, x < y").unwrap(),
+            AST::Quantifier(
+                symbols::FORALL.clone(),
+                Box::new(AST::Sym("x".into())),
+                Box::new(AST::Quantifier(
+                    symbols::EXISTS.clone(),
+                    Box::new(AST::Sym("y".into())),
+                    Box::new(parser.parse(&"x < y").unwrap())
+                ))
+            )
+        );
+        // commas in parentheses don't end the binding, and commas after the body are arguments
+        assert_eq!(
+            parser.parse(&"max(exists n, n = 1, 2)").unwrap(),
+            AST::Function(
+                symbols::SPECIAL_FUNCS["max"].clone(),
+                vec![
+                    AST::Quantifier(
+                        symbols::EXISTS.clone(),
+                        Box::new(AST::Sym("n".into())),
+                        Box::new(parser.parse(&"n = 1").unwrap())
+                    ),
+                    AST::Number("2".into())
+                ]
+            )
+        );
+        // without a binding, a quantifier is just a symbol
+        assert_eq!(
+            parser.parse(&"forall").unwrap(),
+            AST::Sym(symbols::FORALL.clone())
+        );
+        assert_eq!(
+            LatexParser::default()
+                .parse(&r"\forall \epsilon > 0, \exists \delta > 0, P")
+                .unwrap(),
+            parser
+                .parse(&"forall epsilon > 0, exists delta > 0, P")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_groups() {
+        let parser = AsciiParser::default();
+        let group = |kind, inner: &str| AST::Group(kind, Box::new(parser.parse(&inner).unwrap()));
+        let c = || Box::new(AST::Sym("c".into()));
+        assert_eq!(
+            parser.parse(&"[a + b] c").unwrap(),
+            AST::BinaryExpr(
+                BinaryOp::Concat,
+                Box::new(group(DelimKind::Bracket, "a + b")),
+                c()
+            )
+        );
+        assert_eq!(
+            parser.parse(&"(a + b) c").unwrap(),
+            AST::BinaryExpr(
+                BinaryOp::Concat,
+                Box::new(group(DelimKind::Paren, "a + b")),
+                c()
+            )
+        );
+        // redundant parentheses are kept too
+        assert_eq!(
+            parser.parse(&"((x))").unwrap(),
+            AST::Group(DelimKind::Paren, Box::new(group(DelimKind::Paren, "x")))
+        );
+        assert_eq!(
+            parser.parse(&"(a b) + c").unwrap().without_groups(),
+            parser.parse(&"a b + c").unwrap()
+        );
+        // the parentheses around arguments, scripts and fractions aren't groups
+        assert_eq!(
+            parser.parse(&"sin(x) + x_(i) + e^(x + 1)").unwrap(),
+            parser.parse(&"sin x + x_i + e^{x + 1}").unwrap()
+        );
+        assert!(!parser
+            .parse(&"(a + b) / (c d) + d/dx (x^2) - log_(2)(x)")
+            .unwrap()
+            .has_groups());
+    }
+
+    #[test]
+    fn test_matrices() {
+        let parser = AsciiParser::default();
+        let num = |digits: &str| AST::Number(digits.into());
+        let two_by_two = AST::Matrix(vec![vec![num("1"), num("2")], vec![num("3"), num("4")]]);
+        assert_eq!(parser.parse(&"[[1, 2], [3, 4]]").unwrap(), two_by_two);
+        assert_eq!(parser.parse(&"[1, 2; 3, 4]").unwrap(), two_by_two);
+        assert_eq!(parser.parse(&"mat(1, 2; 3, 4)").unwrap(), two_by_two);
+        assert_eq!(
+            LatexParser::default()
+                .parse(&r"\begin{bmatrix} 1 & 2 \\ 3 & 4 \end{bmatrix}")
+                .unwrap(),
+            two_by_two
+        );
+        // vectors are single columns
+        assert_eq!(
+            parser.parse(&"[1; 2; 3]").unwrap(),
+            AST::Matrix(vec![vec![num("1")], vec![num("2")], vec![num("3")]])
+        );
+        // a single list isn't a matrix, and neither are lists of different lengths
+        assert!(matches!(
+            parser.parse(&"[0, 1]").unwrap(),
+            AST::Group(DelimKind::Bracket, _)
+        ));
+        assert!(matches!(
+            parser.parse(&"[[1, 2], [3]]").unwrap(),
+            AST::Group(DelimKind::Bracket, _)
+        ));
+        assert!(matches!(
+            parser.parse(&"mat(1, 2; 3)"),
+            Err(ParseError::RaggedMatrix)
+        ));
+    }
+
+    #[test]
+    fn test_piecewise() {
+        let parser = AsciiParser::default();
+        let abs = parser
+            .parse(&"f(x) = { x^2 if x >= 0; -x otherwise }")
+            .unwrap();
+        let AST::BinaryExpr(_, _, cases) = &abs else {
+            panic!("{:?}", abs);
+        };
+        assert_eq!(
+            **cases,
+            AST::Piecewise(vec![
+                (
+                    parser.parse(&"x^2").unwrap(),
+                    Some(parser.parse(&"x >= 0").unwrap())
+                ),
+                (parser.parse(&"-x").unwrap(), None),
+            ])
+        );
+        // commas before the conditions and the LaTeX environment mean the same thing
+        assert_eq!(
+            parser
+                .parse(&"f(x) = {x^2, if x >= 0; -x, otherwise}")
+                .unwrap(),
+            abs
+        );
+        assert_eq!(
+            LatexParser::default()
+                .parse(&r"f(x) = \begin{cases} x^2 & \text{if } x \ge 0 \\ -x & \text{otherwise} \end{cases}")
+                .unwrap(),
+            abs
+        );
+        // without a condition on every case, it's a set
+        assert!(matches!(
+            parser.parse(&"{x if x > 0; y}").unwrap(),
+            AST::Set(_)
+        ));
+    }
+
+    #[test]
+    fn test_sets() {
+        let parser = AsciiParser::default();
+        let binary = |op: &Op, left: &str, right: &str| {
+            AST::BinaryExpr(
+                BinaryOp::Generic(SymbolBinaryOp {
+                    op: op.clone(),
+                    fixity: Fixity::Infix,
+                }),
+                Box::new(parser.parse(&left).unwrap()),
+                Box::new(parser.parse(&right).unwrap()),
+            )
+        };
+        // set operations bind like arithmetic, and set relations like any other relation
+        assert_eq!(
+            parser.parse(&"x in A union B").unwrap(),
+            binary(&crate::operators::ELEM, "x", "A union B")
+        );
+        assert_eq!(
+            parser.parse(&"A cap B subset A").unwrap(),
+            binary(&crate::operators::SUBSET, "A cap B", "A")
+        );
+        assert_eq!(
+            parser.parse(&r"A \ B").unwrap(),
+            parser.parse(&"A ∖ B").unwrap()
+        );
+        assert_eq!(
+            parser.parse(&"{1, 2, x + 1}").unwrap(),
+            AST::Set(vec![
+                AST::Number("1".into()),
+                AST::Number("2".into()),
+                parser.parse(&"x + 1").unwrap()
+            ])
+        );
+        assert_eq!(parser.parse(&"{}").unwrap(), AST::Set(vec![]));
+        // the bar splits the whole set, even around looser operators
+        assert_eq!(
+            parser.parse(&"{x in RR | x > 0 or x < -1}").unwrap(),
+            AST::SetBuilder(
+                Box::new(binary(&crate::operators::ELEM, "x", "RR")),
+                Box::new(parser.parse(&"x > 0 or x < -1").unwrap())
+            )
+        );
+        assert_eq!(
+            parser.parse(&"{n : n in NN}").unwrap(),
+            parser.parse(&"{n | n ∈ ℕ}").unwrap()
+        );
+        // braces after a script still group it
+        assert_eq!(
+            parser.parse(&"x^{2} {1}").unwrap(),
+            AST::BinaryExpr(
+                BinaryOp::Concat,
+                Box::new(parser.parse(&"x^2").unwrap()),
+                Box::new(AST::Set(vec![AST::Number("1".into())]))
+            )
+        );
+        assert_eq!(
+            LatexParser::default()
+                .parse(&r"\left\{ x \in \mathbb{R} \mid x \neq 0 \right\} \setminus \{0\}")
+                .unwrap(),
+            parser.parse(&r"{x in RR | x != 0} \ {0}").unwrap()
+        );
+        assert!(matches!(
+            parser.parse(&"{1, 2"),
+            Err(ParseError::MismatchedParentheses)
+        ));
+    }
+
+    #[test]
+    fn test_floor_ceil() {
+        let parser = AsciiParser::default();
+        let fenced = |kind, inner: &str| AST::Fenced(kind, Box::new(parser.parse(&inner).unwrap()));
+        assert_eq!(
+            parser.parse(&"floor(x / 2)").unwrap(),
+            fenced(DelimKind::Floor, "x / 2")
+        );
+        assert_eq!(
+            parser.parse(&"⌊x / 2⌋").unwrap(),
+            fenced(DelimKind::Floor, "x / 2")
+        );
+        // the ) closes whatever opened it, so the parens inside stay parens
+        assert_eq!(
+            parser.parse(&"ceil((x + 1) (y))").unwrap(),
+            fenced(DelimKind::Ceil, "(x + 1) (y)")
+        );
+        assert_eq!(parser.parse(&"⌈y⌉").unwrap(), fenced(DelimKind::Ceil, "y"));
+        assert_eq!(
+            parser.parse(&"<<a, b>>").unwrap(),
+            fenced(DelimKind::Angle, "a, b")
+        );
+        assert_eq!(
+            parser.parse(&"⟨a, b⟩").unwrap(),
+            fenced(DelimKind::Angle, "a, b")
+        );
+        assert_eq!(
+            parser.parse(&"floor(x) + ceil(y)").unwrap(),
+            LatexParser::default()
+                .parse(&r"\left\lfloor x \right\rfloor + \lceil y \rceil")
+                .unwrap()
+        );
+        assert!(matches!(
+            parser.parse(&"floor(x"),
+            Err(ParseError::MismatchedParentheses)
+        ));
+    }
+
+    #[test]
+    fn test_bars() {
+        let parser = AsciiParser::default();
+        let abs =
+            |inner: &str| AST::Fenced(DelimKind::Abs, Box::new(parser.parse(&inner).unwrap()));
+        let norm =
+            |inner: &str| AST::Fenced(DelimKind::Norm, Box::new(parser.parse(&inner).unwrap()));
+        assert_eq!(parser.parse(&"|x - 1|").unwrap(), abs("x - 1"));
+        assert_eq!(parser.parse(&"||v||").unwrap(), norm("v"));
+        assert_eq!(parser.parse(&"‖v‖").unwrap(), norm("v"));
+        // a bar after an operand closes what's open, or opens a new pair next to it
+        assert_eq!(
+            parser.parse(&"|x||y|").unwrap(),
+            AST::BinaryExpr(BinaryOp::Concat, Box::new(abs("x")), Box::new(abs("y")))
+        );
+        assert_eq!(
+            parser.parse(&"2|x| + 1").unwrap(),
+            parser.parse(&"2 (|x|) + 1").unwrap().without_groups()
+        );
+        assert_eq!(
+            parser.parse(&"| |x| - 1 |").unwrap(),
+            AST::Fenced(DelimKind::Abs, Box::new(parser.parse(&"|x| - 1").unwrap()))
+        );
+        // bars nest, and an opening || is only a norm if a double bar closes it
+        let difference = parser.parse(&"| |x| - |y| |").unwrap();
+        assert_eq!(difference, abs("|x| - |y|"));
+        assert_eq!(parser.parse(&"||x| - |y||").unwrap(), difference);
+        assert_eq!(
+            LatexParser::default().parse(&"||x| - |y||").unwrap(),
+            difference
+        );
+        assert_eq!(parser.parse(&"|x - |y||").unwrap(), abs("x - |y|"));
+        assert_eq!(parser.parse(&"||x| + 1|").unwrap(), abs("|x| + 1"));
+        assert_eq!(parser.parse(&"||v| x|").unwrap(), abs("|v| x"));
+        assert_eq!(parser.parse(&"‖|x| - 1‖").unwrap(), norm("|x| - 1"));
+        // || with nothing to open or close is an or
+        assert_eq!(
+            parser.parse(&"a || b").unwrap(),
+            parser.parse(&"a or b").unwrap()
+        );
+        // and the first bar in a set splits it
+        assert_eq!(
+            parser.parse(&"{x | |x| < 1}").unwrap(),
+            AST::SetBuilder(
+                Box::new(AST::Sym("x".into())),
+                Box::new(parser.parse(&"(|x|) < 1").unwrap().without_groups())
+            )
+        );
+        assert_eq!(
+            LatexParser::default()
+                .parse(&r"\left| x \right| + \lVert v \rVert - \|w\|")
+                .unwrap(),
+            parser.parse(&"|x| + ||v|| - ||w||").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_implicit_multiplication() {
+        let sym = |name: &str| AST::Sym(name.into());
+        let num = |digits: &str| AST::Number(digits.into());
+        let bin = |op, l, r| AST::BinaryExpr(op, Box::new(l), Box::new(r));
+        let concat = |l, r| bin(BinaryOp::Concat, l, r);
+        let frac = |l, r| bin(BinaryOp::Frac, l, r);
+        let calculator = AsciiParser::default();
+        let physicist = AsciiParser {
+            implicit_multiplication: ImplicitMultiplication::TighterThanDivision,
+            ..AsciiParser::default()
+        };
+
+        assert_eq!(
+            calculator.parse(&"1/2x").unwrap(),
+            concat(frac(num("1"), num("2")), sym("x"))
+        );
+        assert_eq!(
+            physicist.parse(&"1/2x").unwrap(),
+            frac(num("1"), concat(num("2"), sym("x")))
+        );
+        assert_eq!(
+            calculator.parse(&"2x/3y").unwrap(),
+            concat(frac(concat(num("2"), sym("x")), num("3")), sym("y"))
+        );
+        assert_eq!(
+            physicist.parse(&"2x/3y").unwrap(),
+            frac(concat(num("2"), sym("x")), concat(num("3"), sym("y")))
+        );
+        assert_eq!(
+            physicist.parse(&"{1/2x}").unwrap(),
+            AST::Set(vec![frac(num("1"), concat(num("2"), sym("x")))])
+        );
+        for parser in [calculator, physicist] {
+            assert_eq!(
+                parser.parse(&"2x^2").unwrap(),
+                concat(num("2"), bin(BinaryOp::Power, sym("x"), num("2")))
+            );
+            assert_eq!(
+                parser.parse(&"a b/c").unwrap(),
+                frac(concat(sym("a"), sym("b")), sym("c"))
+            );
+            assert_eq!(
+                parser.parse(&"2 x y").unwrap(),
+                concat(concat(num("2"), sym("x")), sym("y"))
+            );
+        }
+    }
+
     #[test]
     fn test_simple_frac() {
         let tokens = Tokenizer::default().tokenize("1 + (2 * 3)");
@@ -0,0 +1,868 @@
+//! Parser for LaTeX math.
+
+use crate::ast::{BinaryOp, Symbol, AST};
+use crate::delimiter::{self, DelimDir, DelimKind, Delimiter};
+use crate::operators;
+use crate::parsers::ascii::{
+    cases_condition, derivative_token, parse_tokens, ImplicitMultiplication, ParseError,
+};
+use crate::parsers::token::{bar_delim, expects_operand, split_number, Locale, Prefix, Token};
+use crate::symbols;
+
+/// Commands that only control spacing, which has no meaning in the tree.
+const SPACING_COMMANDS: [&str; 7] = [r"\,", r"\:", r"\;", r"\!", r"\ ", r"\quad", r"\qquad"];
+
+/// Commands that only control the size of the delimiter after them, like `\left(`.
+const SIZING_COMMANDS: [&str; 14] = [
+    r"\left", r"\right", r"\big", r"\Big", r"\bigg", r"\Bigg", r"\bigl", r"\bigr", r"\Bigl",
+    r"\Bigr", r"\biggl", r"\biggr", r"\Biggl", r"\Biggr",
+];
+
+/// Environments for matrices, which differ only in the delimiters around them.
+const MATRIX_ENVIRONMENTS: [&str; 6] = [
+    "matrix", "pmatrix", "bmatrix", "Bmatrix", "vmatrix", "Vmatrix",
+];
+
+/// Commands that typeset their argument as upright text.
+const TEXT_COMMANDS: [&str; 3] = [r"\text", r"\mathrm", r"\operatorname"];
+
+/// Splits a command (a backslash followed by either a run of letters or a single other character)
+/// off the front of the input.
+fn split_command(input: &str) -> (&str, &str) {
+    let after_slash = &input[1..];
+    let len = match after_slash.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => after_slash
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(after_slash.len()),
+        Some(c) => c.len_utf8(),
+        None => 0,
+    };
+    input.split_at(len + 1)
+}
+
+/// Splits a single argument off the front of the input, returning the argument and the rest. An
+/// argument is either a group in braces, a command, or a single character: `\frac12` is `\frac{1}{2}`.
+fn split_arg(input: &str) -> Result<(&str, &str), ParseError> {
+    let input = input.trim_start();
+    match input.chars().next() {
+        None => Err(ParseError::MissingOperands),
+        Some('{') => {
+            let mut depth = 0;
+            let mut chars = input.char_indices();
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    // skip escaped braces, which don't group anything
+                    '\\' => {
+                        chars.next();
+                    }
+                    '{' => depth += 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Ok((&input[1..i], &input[i + 1..]));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Err(ParseError::MismatchedParentheses)
+        }
+        Some('}') => Err(ParseError::MismatchedParentheses),
+        Some('\\') => Ok(split_command(input)),
+        Some(c) => Ok(input.split_at(c.len_utf8())),
+    }
+}
+
+/// Replaces the braces LaTeX needs around a decimal comma, as in `3{,}14`, with the decimal
+/// separator, so that the number reads the same way in any locale. Braced commas anywhere but
+/// between digits are left alone.
+fn replace_braced_commas(input: &str, decimal_sep: char) -> String {
+    let mut replaced = String::new();
+    let mut rest = input;
+    while let Some(i) = rest.find("{,}") {
+        let (before, after) = (&rest[..i], &rest[i + 3..]);
+        replaced.push_str(before);
+        if before.ends_with(|c: char| c.is_ascii_digit())
+            && after.starts_with(|c: char| c.is_ascii_digit())
+        {
+            replaced.push(decimal_sep);
+        } else {
+            replaced.push_str("{,}");
+        }
+        rest = after;
+    }
+    replaced.push_str(rest);
+    replaced
+}
+
+/// Splits the body of an environment off the front of the input, up to the `\end` that matches its
+/// `\begin`, returning the body and what's after the `\end{...}`.
+fn split_env(input: &str) -> Result<(&str, &str), ParseError> {
+    let mut depth = 0;
+    let mut i = 0;
+    while let Some(c) = input[i..].chars().next() {
+        if c != '\\' {
+            i += c.len_utf8();
+            continue;
+        }
+        let (command, after) = split_command(&input[i..]);
+        match command {
+            r"\begin" => depth += 1,
+            r"\end" if depth == 0 => {
+                let (_, rest) = split_arg(after)?;
+                return Ok((&input[..i], rest));
+            }
+            r"\end" => depth -= 1,
+            _ => {}
+        }
+        i += command.len();
+    }
+    Err(ParseError::MismatchedParentheses)
+}
+
+/// Splits the body of an environment into its rows, at each `\\`, and each row into its cells, at
+/// each `&`, leaving alone the ones inside braces or other environments.
+fn split_cells(body: &str) -> Vec<Vec<&str>> {
+    let mut rows = vec![vec![]];
+    let mut depth = 0;
+    let mut start = 0;
+    let mut i = 0;
+    while let Some(c) = body[i..].chars().next() {
+        let len = match c {
+            '\\' => {
+                let (command, _) = split_command(&body[i..]);
+                match command {
+                    r"\begin" => depth += 1,
+                    r"\end" => depth -= 1,
+                    r"\\" if depth == 0 => {
+                        rows.last_mut().unwrap().push(&body[start..i]);
+                        rows.push(vec![]);
+                        start = i + command.len();
+                    }
+                    _ => {}
+                }
+                command.len()
+            }
+            '{' => {
+                depth += 1;
+                1
+            }
+            '}' => {
+                depth -= 1;
+                1
+            }
+            '&' if depth == 0 => {
+                rows.last_mut().unwrap().push(&body[start..i]);
+                start = i + 1;
+                1
+            }
+            c => c.len_utf8(),
+        };
+        i += len;
+    }
+    rows.last_mut().unwrap().push(&body[start..]);
+    // a \\ after the last row doesn't start another one
+    rows.retain(|row| row.iter().any(|cell| !cell.trim().is_empty()));
+    rows
+}
+
+/// Splits the variable of a differential off the front of the input, like the `x` after the `d` in
+/// `\int f(x)\,dx`: a single letter, or a Greek letter command, maybe after some space. Returns the
+/// differential and the rest of the input.
+fn split_differential(input: &str) -> Option<(Symbol, &str)> {
+    let input = input.trim_start();
+    let d = |var: &Symbol| {
+        Symbol::new(
+            &format!("d{}", var.unicode_repr),
+            &format!("d{}", var.ascii_repr),
+            &format!("d{}", var.latex_repr),
+            vec![],
+        )
+    };
+    match input.chars().next()? {
+        c if c.is_ascii_alphabetic() => Some((d(&Symbol::from(c.to_string())), &input[1..])),
+        '\\' => {
+            let (command, after) = split_command(input);
+            symbols::ALL_SYMBOLS
+                .iter()
+                .find(|sym| {
+                    sym.latex_repr == command
+                        && sym.unicode_repr.chars().count() == 1
+                        && sym.unicode_repr.chars().all(char::is_alphabetic)
+                })
+                .map(|sym| (d(sym), after))
+        }
+        _ => None,
+    }
+}
+
+/// Finds the innermost delimiter that's still open, returning its position and its kind.
+fn innermost_open(tokens: &[Token]) -> Option<(usize, DelimKind)> {
+    let mut open = vec![];
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Delim(delim) if delim.dir == DelimDir::Left => open.push((i, delim.kind)),
+            Token::Delim(_) => {
+                open.pop();
+            }
+            _ => {}
+        }
+    }
+    open.pop()
+}
+
+/// Returns whether an integral has come up, so that a `d` might start its differential.
+fn in_integral(tokens: &[Token]) -> bool {
+    tokens.iter().any(
+        |token| matches!(token, Token::Prefix(Prefix::BigOp(sym, _, _)) if *sym == *symbols::INT),
+    )
+}
+
+/// A parser for LaTeX math-mode input, like `\frac{1}{2} \alpha^{2}`. Every `latex_repr` of the
+/// predefined symbols is recognized, along with the structural commands (`\frac`, `\sqrt`, `\left`,
+/// and so on) that don't have a plain-text equivalent. As in LaTeX itself, a run of letters is a
+/// product of single-letter variables, not one long name.
+#[derive(Debug, Clone, Default)]
+pub struct LatexParser {
+    /// How numbers and lists are written.
+    pub locale: Locale,
+    /// How tightly implicit multiplication binds.
+    pub implicit_multiplication: ImplicitMultiplication,
+}
+
+impl LatexParser {
+    /// Tokenizes LaTeX input into a list of tokens.
+    pub fn tokenize(&self, input: &str) -> Result<Vec<Token>, ParseError> {
+        let input = replace_braced_commas(input, self.locale.decimal_sep);
+        let mut rest = input.as_str();
+        let mut tokens = vec![];
+        'parse: while let Some(c) = rest.chars().next() {
+            if c.is_whitespace() {
+                rest = &rest[c.len_utf8()..];
+                continue 'parse;
+            }
+
+            if c == '\\' {
+                let (command, after) = split_command(rest);
+                rest = after;
+                rest = self.push_command(command, rest, &mut tokens)?;
+                continue 'parse;
+            }
+
+            if let Some((num, after)) = split_number(rest, &self.locale) {
+                tokens.push(Token::Expr(num));
+                rest = after;
+                continue 'parse;
+            }
+
+            // the differential of an integral, written dx or d x
+            if c == 'd' && in_integral(&tokens) {
+                if let Some((d, after)) = split_differential(&rest[1..]) {
+                    tokens.push(Token::Operand(d));
+                    rest = after;
+                    continue 'parse;
+                }
+            }
+
+            if c.is_ascii_alphabetic() {
+                tokens.push(Token::Operand(Symbol::from(c.to_string())));
+                rest = &rest[1..];
+                continue 'parse;
+            }
+
+            match c {
+                '{' => tokens.push(Token::Delim(delimiter::LGROUP)),
+                '&' => tokens.push(Token::Operator(operators::COMMA.clone())),
+                // a list separator other than a comma works like one
+                c if c == self.locale.list_sep && c != ',' => {
+                    tokens.push(Token::Operator(operators::COMMA.clone()))
+                }
+                '}' => tokens.push(Token::Delim(delimiter::RGROUP)),
+                // scripts take a single argument, so x^23 is x^{2} 3
+                '^' | '_' => {
+                    let (arg, after) = split_arg(&rest[1..])?;
+                    // degrees and transposes are written as superscripts
+                    let postfix = match arg.trim() {
+                        r"\circ" => Some(&*operators::DEGREE),
+                        "T" | r"\top" | r"\intercal" => Some(&*operators::TRANSPOSE),
+                        _ => None,
+                    };
+                    if let Some(op) =
+                        postfix.filter(|_| c == '^' && !expects_operand(tokens.last()))
+                    {
+                        tokens.push(Token::Operator(op.clone()));
+                        rest = after;
+                        continue 'parse;
+                    }
+                    let op = if c == '^' {
+                        &*operators::POWER
+                    } else {
+                        &*operators::SUBSCRIPT
+                    };
+                    tokens.push(Token::Operator(op.clone()));
+                    self.push_group(arg, &mut tokens)?;
+                    rest = after;
+                    continue 'parse;
+                }
+                // bars open or close absolute values depending on what's around them
+                '|' => {
+                    rest = &rest[1..];
+                    match bar_delim(DelimKind::Abs, &tokens, rest) {
+                        Some(delim) => tokens.push(Token::Delim(delim)),
+                        None => tokens.push(Token::Operator(operators::MID.clone())),
+                    }
+                    continue 'parse;
+                }
+                _ => {
+                    for delim in delimiter::DELIMS.iter() {
+                        if let Some(repr) = delim.get_symbol().match_front(rest) {
+                            tokens.push(Token::Delim(*delim));
+                            rest = &rest[repr.len()..];
+                            continue 'parse;
+                        }
+                    }
+
+                    let curr_ops = operators::next_ops(expects_operand(tokens.last()));
+                    if let Some((op, repr)) = operators::longest_match(&curr_ops, rest) {
+                        tokens.push(Token::Operator(op.clone()));
+                        rest = &rest[repr.len()..];
+                        continue 'parse;
+                    }
+
+                    // any Unicode symbols that made their way into the input
+                    for sym in symbols::ALL_SYMBOLS.iter() {
+                        if sym.unicode_repr.starts_with(|c: char| !c.is_ascii())
+                            && rest.starts_with(sym.unicode_repr.as_str())
+                        {
+                            tokens.push(Token::Operand(sym.clone()));
+                            rest = &rest[sym.unicode_repr.len()..];
+                            continue 'parse;
+                        }
+                    }
+
+                    tokens.push(Token::Operand(Symbol::from(c.to_string())));
+                }
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+        tokens.push(Token::End);
+        Ok(tokens)
+    }
+
+    /// Parses a group's contents on their own.
+    fn parse_arg(&self, arg: &str) -> Result<AST, ParseError> {
+        parse_tokens(self.tokenize(arg)?, self.implicit_multiplication)
+    }
+
+    /// Pushes a group's contents onto the token list, wrapped in an invisible group so it stays
+    /// together.
+    fn push_group(&self, arg: &str, tokens: &mut Vec<Token>) -> Result<(), ParseError> {
+        let mut inner = self.tokenize(arg)?;
+        // get rid of the end-of-expression marker
+        inner.pop();
+        tokens.push(Token::Delim(delimiter::LGROUP));
+        tokens.append(&mut inner);
+        tokens.push(Token::Delim(delimiter::RGROUP));
+        Ok(())
+    }
+
+    /// Handles a single command, pushing whatever tokens it produces. Returns the input left after
+    /// the command's arguments.
+    fn push_command<'a>(
+        &self,
+        command: &str,
+        rest: &'a str,
+        tokens: &mut Vec<Token>,
+    ) -> Result<&'a str, ParseError> {
+        if SPACING_COMMANDS.contains(&command) {
+            return Ok(rest);
+        }
+
+        if SIZING_COMMANDS.contains(&command) {
+            // \left. is an invisible delimiter, which takes the kind of whatever closes it
+            if command == r"\left" {
+                if let Some(after) = rest.trim_start().strip_prefix('.') {
+                    tokens.push(Token::Delim(delimiter::LGROUP));
+                    return Ok(after);
+                }
+            }
+            // braces have to close before a \right, so an open group must be from a \left.
+            if let (r"\right", Some((i, DelimKind::Group))) = (command, innermost_open(tokens)) {
+                let (delim, after) = split_arg(rest)?;
+                let kind = match delim {
+                    "." => DelimKind::Group,
+                    // the bar in \left. x^2 \right|_0^1 means the same as the brackets in
+                    // [x^2]_0^1, evaluating between the bounds
+                    "|" | r"\vert" | r"\rvert" => DelimKind::Bracket,
+                    _ => delimiter::DELIMS
+                        .iter()
+                        .find(|d| {
+                            d.dir == DelimDir::Right && d.get_symbol().reprs().contains(&delim)
+                        })
+                        .map_or(DelimKind::Group, |d| d.kind),
+                };
+                tokens[i] = Token::Delim(Delimiter {
+                    dir: DelimDir::Left,
+                    kind,
+                });
+                tokens.push(Token::Delim(Delimiter {
+                    dir: DelimDir::Right,
+                    kind,
+                }));
+                return Ok(after);
+            }
+            return Ok(rest.trim_start().strip_prefix('.').unwrap_or(rest));
+        }
+
+        match command {
+            // a matrix is written like mat(a, b; c, d), with & between the entries and \\ between
+            // the rows
+            r"\begin" | r"\end" => {
+                let (env, rest) = split_arg(rest)?;
+                let env = env.trim();
+                if MATRIX_ENVIRONMENTS.contains(&env) {
+                    if command == r"\begin" {
+                        tokens.push(Token::Function(symbols::MATRIX.clone()));
+                        tokens.push(Token::Delim(delimiter::LPAR));
+                    } else {
+                        tokens.push(Token::Delim(delimiter::RPAR));
+                    }
+                } else if env == "cases" && command == r"\begin" {
+                    // each row is a value and then its condition, which doesn't need an if
+                    let (body, rest) = split_env(rest)?;
+                    let mut cases = vec![];
+                    for row in split_cells(body) {
+                        let value = self.parse_arg(row[0])?;
+                        let condition = match row.get(1..) {
+                            Some(condition) if !condition.is_empty() => cases_condition(
+                                self.tokenize(&condition.join("&"))?,
+                                self.implicit_multiplication,
+                            )?,
+                            _ => None,
+                        };
+                        cases.push((value, condition));
+                    }
+                    tokens.push(Token::Expr(AST::Piecewise(cases)));
+                    return Ok(rest);
+                }
+                return Ok(rest);
+            }
+            r"\\" => {
+                tokens.push(Token::Operator(operators::ROW_SEP.clone()));
+                return Ok(rest);
+            }
+            r"\frac" | r"\dfrac" | r"\tfrac" => {
+                let (num, rest) = split_arg(rest)?;
+                let (denom, rest) = split_arg(rest)?;
+                let (num, denom) = (self.parse_arg(num)?, self.parse_arg(denom)?);
+                // \frac{dy}{dx} and \frac{d}{dx} are derivatives, not fractions
+                tokens.push(derivative_token(&num, &denom).unwrap_or_else(|| {
+                    Token::Expr(AST::BinaryExpr(
+                        BinaryOp::Frac,
+                        Box::new(num),
+                        Box::new(denom),
+                    ))
+                }));
+                return Ok(rest);
+            }
+            r"\sqrt" => {
+                // an optional index, as in \sqrt[3]{x}
+                let (index, rest) = match rest.trim_start().strip_prefix('[') {
+                    Some(after) => match after.find(']') {
+                        Some(i) => (Some(&after[..i]), &after[i + 1..]),
+                        None => return Err(ParseError::MismatchedParentheses),
+                    },
+                    None => (None, rest),
+                };
+                let (radicand, rest) = split_arg(rest)?;
+                let radicand = self.parse_arg(radicand)?;
+                let index = match index {
+                    Some(index) => self.parse_arg(index)?,
+                    None => AST::Number("2".into()),
+                };
+                tokens.push(Token::Expr(AST::BinaryExpr(
+                    BinaryOp::Root,
+                    Box::new(index),
+                    Box::new(radicand),
+                )));
+                return Ok(rest);
+            }
+            _ => {}
+        }
+
+        // blackboard bold letters are sets of numbers, like \mathbb{R}
+        if command == r"\mathbb" {
+            let (letter, rest) = split_arg(rest)?;
+            let latex = format!(r"\mathbb{{{}}}", letter.trim());
+            tokens.push(Token::Operand(
+                symbols::ALL_SYMBOLS
+                    .iter()
+                    .find(|sym| sym.latex_repr == latex)
+                    .cloned()
+                    .unwrap_or_else(|| Symbol::new(letter.trim(), letter.trim(), &latex, vec![])),
+            ));
+            return Ok(rest);
+        }
+
+        if TEXT_COMMANDS.contains(&command) {
+            let (name, rest) = split_arg(rest)?;
+            let name = name.trim();
+            // an upright d in an integral, as in \mathrm{d}x
+            if name == "d" && in_integral(tokens) {
+                if let Some((d, after)) = split_differential(rest) {
+                    tokens.push(Token::Operand(d));
+                    return Ok(after);
+                }
+            }
+            match symbols::SPECIAL_FUNCS.get(name) {
+                Some(sym) => tokens.push(Token::Function(sym.clone())),
+                None if command == r"\operatorname" => tokens.push(Token::Function(Symbol::new(
+                    name,
+                    name,
+                    &format!(r"\operatorname{{{}}}", name),
+                    vec![],
+                ))),
+                None => tokens.push(Token::Operand(Symbol::new(
+                    name,
+                    name,
+                    &format!(r"{}{{{}}}", command, name),
+                    vec![],
+                ))),
+            }
+            return Ok(rest);
+        }
+
+        // special functions, including the squared and inverse versions like \sin^2
+        if let Some(name) = command.strip_prefix('\\') {
+            if symbols::SPECIAL_FUNCS.contains_key(name) {
+                for (suffix, key) in [
+                    ("^2", format!("{}^2", name)),
+                    ("^{2}", format!("{}^2", name)),
+                    ("^{-1}", format!("{}^-1", name)),
+                ] {
+                    if let Some(after) = rest.trim_start().strip_prefix(suffix) {
+                        tokens.push(Token::Function(symbols::SPECIAL_FUNCS[&key].clone()));
+                        return Ok(after);
+                    }
+                }
+                tokens.push(Token::Function(symbols::SPECIAL_FUNCS[name].clone()));
+                return Ok(rest);
+            }
+        }
+
+        // big operators, whose bounds are the scripts after them
+        if let Some(sym) = symbols::BIG_OPS
+            .iter()
+            .find(|sym| sym.latex_repr == command)
+        {
+            tokens.push(Token::Prefix(Prefix::BigOp(sym.clone(), None, None)));
+            return Ok(rest);
+        }
+
+        // bars, which might be written the same on both sides
+        if let Some(delim) = delimiter::BARS
+            .iter()
+            .find(|delim| delim.get_symbol().reprs().contains(&command))
+        {
+            tokens.push(Token::Delim(*delim));
+            return Ok(rest);
+        }
+        let bar = match command {
+            r"\|" | r"\Vert" => Some(DelimKind::Norm),
+            r"\vert" => Some(DelimKind::Abs),
+            _ => None,
+        };
+        if let Some(kind) = bar {
+            match bar_delim(kind, tokens, rest) {
+                Some(delim) => tokens.push(Token::Delim(delim)),
+                None => tokens.push(Token::Operator(operators::MID.clone())),
+            }
+            return Ok(rest);
+        }
+
+        let curr_ops = operators::next_ops(expects_operand(tokens.last()));
+        if let Some(op) = curr_ops.iter().find(|op| op.sym.reprs().contains(&command)) {
+            tokens.push(Token::Operator(op.clone()));
+            return Ok(rest);
+        }
+
+        if let Some(delim) = delimiter::DELIMS
+            .iter()
+            .find(|delim| delim.get_symbol().reprs().contains(&command))
+        {
+            tokens.push(Token::Delim(*delim));
+            return Ok(rest);
+        }
+
+        match symbols::ALL_SYMBOLS
+            .iter()
+            .find(|sym| sym.reprs().contains(&command))
+        {
+            Some(sym) => tokens.push(Token::Operand(sym.clone())),
+            // an unknown command, which we keep as is
+            None => {
+                let name = command.trim_start_matches('\\');
+                tokens.push(Token::Operand(Symbol::new(name, name, command, vec![])))
+            }
+        }
+        Ok(rest)
+    }
+}
+
+impl<T> super::ASTParser<T> for LatexParser
+where
+    T: ToString,
+{
+    type ParseError = ParseError;
+
+    fn parse(&self, input: &T) -> Result<AST, Self::ParseError> {
+        parse_tokens(
+            self.tokenize(&input.to_string())?,
+            self.implicit_multiplication,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter::Formatter;
+    use crate::formatters::{
+        ascii::AsciiFormatter, latex::LatexFormatter, unicode::UnicodeFormatter,
+    };
+    use crate::parsers::{ASTParser, AsciiParser};
+
+    #[test]
+    fn test_split_arg() {
+        assert_eq!(split_arg("{a + {b}} c").unwrap(), ("a + {b}", " c"));
+        assert_eq!(split_arg("12").unwrap(), ("1", "2"));
+        assert_eq!(split_arg(r" \alpha + 1").unwrap(), (r"\alpha", " + 1"));
+        assert!(split_arg("{a + b").is_err());
+    }
+
+    #[test]
+    fn test_same_as_ascii() {
+        let latex = LatexParser::default();
+        let ascii = AsciiParser::default();
+        assert_eq!(
+            latex.parse(&r"\frac{2}{\sin\left(\mu\right) + 1}").unwrap(),
+            ascii.parse(&"2 / (sin mu + 1)").unwrap()
+        );
+        assert_eq!(
+            latex.parse(&r"\mu^{3 \cdot (4 + 5)}").unwrap(),
+            ascii.parse(&"mu ^ (3 * (4 + 5))").unwrap()
+        );
+        assert_eq!(
+            latex.parse(&r"\cos^2\left(A\right) + \sin^{2}(B)").unwrap(),
+            ascii.parse(&"cos^2(A) + sin^2(B)").unwrap()
+        );
+        assert_eq!(
+            latex.parse(&r"x_i^2 + \log_{10} y_{n+1}").unwrap(),
+            ascii.parse(&"x_i^2 + log_10 y_(n + 1)").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_implicit_multiplication() {
+        let num = |digits: &str| AST::Number(digits.into());
+        let bin = |op, l, r| AST::BinaryExpr(op, Box::new(l), Box::new(r));
+        let x = AST::Sym("x".into());
+        let physicist = LatexParser {
+            implicit_multiplication: ImplicitMultiplication::TighterThanDivision,
+            ..LatexParser::default()
+        };
+        assert_eq!(
+            LatexParser::default().parse(&"1/2 x").unwrap(),
+            bin(
+                BinaryOp::Concat,
+                bin(BinaryOp::Frac, num("1"), num("2")),
+                x.clone()
+            )
+        );
+        assert_eq!(
+            physicist.parse(&"1/2 x").unwrap(),
+            bin(
+                BinaryOp::Frac,
+                num("1"),
+                bin(BinaryOp::Concat, num("2"), x.clone())
+            )
+        );
+        // \frac is a group of its own, whatever the setting
+        assert_eq!(
+            physicist.parse(&r"\frac{1}{2} x").unwrap(),
+            LatexParser::default().parse(&r"\frac{1}{2} x").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_differentials() {
+        let latex = LatexParser::default();
+        let tree = AsciiParser::default().parse(&"int_0^1 f(x) dx").unwrap();
+        for input in [
+            r"\int_0^1 f(x)\,dx",
+            r"\int_0^1 f(x) d x",
+            r"\int_{0}^{1} f(x) \, \mathrm{d}x",
+        ] {
+            assert_eq!(latex.parse(&input).unwrap(), tree);
+        }
+        assert_eq!(
+            LatexFormatter::default().format(&latex.parse(&r"\int_0^1 f(x)\,dx").unwrap()),
+            r"\int_{0}^{1} f(x)\,dx"
+        );
+        assert_eq!(
+            UnicodeFormatter::default()
+                .format(&latex.parse(&r"\int \sin\theta \,d\theta").unwrap()),
+            "∫ sin(θ) dθ"
+        );
+        // a d anywhere else is just a letter
+        assert_eq!(
+            latex.parse(&"d x").unwrap(),
+            AsciiParser::default().parse(&"d x").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cases() {
+        let latex = LatexParser::default();
+        let ascii = AsciiParser::default();
+        let tree = ascii.parse(&"{1, if x > 0; 0, otherwise}").unwrap();
+        for input in [
+            r"\begin{cases} 1 & x > 0 \\ 0 & \text{otherwise} \end{cases}",
+            r"\begin{cases} 1 & \text{if } x > 0 \\ 0 & \text{otherwise} \\ \end{cases}",
+            r"\begin{cases} 1 & x > 0 \\ 0 \end{cases}",
+        ] {
+            assert_eq!(latex.parse(&input).unwrap(), tree);
+        }
+        assert_eq!(
+            latex
+                .parse(&r"|x| = \begin{cases} x & x \geq 0 \\ -x & x < 0 \end{cases}")
+                .unwrap(),
+            ascii.parse(&"|x| = {x, if x >= 0; -x, if x < 0}").unwrap()
+        );
+        // the rows of a matrix inside a case stay in the matrix
+        assert_eq!(
+            latex
+                .parse(&r"\begin{cases} \begin{pmatrix} 1 & 0 \\ 0 & 1 \end{pmatrix} & n = 0 \\ A^n & \text{otherwise} \end{cases}")
+                .unwrap(),
+            ascii
+                .parse(&"{mat(1, 0; 0, 1), if n = 0; A^n, otherwise}")
+                .unwrap()
+        );
+        assert!(latex.parse(&r"\begin{cases} 1 & x > 0").is_err());
+    }
+
+    #[test]
+    fn test_commands() {
+        let latex = LatexParser::default();
+        let ascii = AsciiParser::default();
+        // every tree here comes back the same from its ASCII and LaTeX output
+        for input in [
+            r"a_1 + \ldots + a_n - \cdots",
+            r"\operatorname{sgn}(x) \operatorname{lcm}(a, b)",
+            r"\left. \frac{x^2}{2} \right|_0^1",
+        ] {
+            let tree = latex.parse(&input).unwrap();
+            let as_ascii = AsciiFormatter::default().format(&tree);
+            assert_eq!(
+                ascii.parse(&as_ascii).unwrap().without_groups(),
+                tree.without_groups(),
+                "{} -> {}",
+                input,
+                as_ascii
+            );
+            let as_latex = LatexFormatter::default().format(&tree);
+            assert_eq!(
+                latex.parse(&as_latex).unwrap().without_groups(),
+                tree.without_groups(),
+                "{} -> {}",
+                input,
+                as_latex
+            );
+        }
+        // unknown commands keep their name apart from what's next to them
+        let tree = latex
+            .parse(&r"\unknowncmd x + \operatorname{foo} y")
+            .unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "unknowncmd x + foo(y)"
+        );
+        assert_eq!(
+            AsciiFormatter::default().format(&tree),
+            "unknowncmd x + foo(y)"
+        );
+        assert_eq!(
+            latex
+                .parse(&LatexFormatter::default().format(&tree))
+                .unwrap()
+                .without_groups(),
+            tree
+        );
+        assert_eq!(
+            latex.parse(&r"\operatorname{sgn}(x)").unwrap(),
+            ascii.parse(&"sgn(x)").unwrap()
+        );
+        // the evaluation bar works like brackets, and \left. closes with whatever \right has
+        assert_eq!(
+            latex.parse(&r"\left. x \right|_{0}").unwrap(),
+            ascii.parse(&"[x]_0").unwrap()
+        );
+        assert_eq!(
+            latex.parse(&r"\left. x + 1 \right.").unwrap(),
+            ascii.parse(&"x + 1").unwrap()
+        );
+        assert_eq!(
+            latex.parse(&r"\left. \left. x \right) \right]").unwrap(),
+            ascii.parse(&"[(x)]").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_locale() {
+        let num = |digits: &str| AST::Number(digits.into());
+        assert_eq!(
+            LatexParser::default().parse(&"3{,}14").unwrap(),
+            num("3.14")
+        );
+        let european = LatexParser {
+            locale: Locale::decimal_comma(),
+            ..LatexParser::default()
+        };
+        let ascii = AsciiParser {
+            tokenizer: crate::parsers::token::Tokenizer {
+                locale: Locale::decimal_comma(),
+                ..Default::default()
+            },
+            ..AsciiParser::default()
+        };
+        assert_eq!(european.parse(&"3{,}14").unwrap(), num("3.14"));
+        assert_eq!(
+            european.parse(&r"\max(1,5; 2{,}5) + 1.000").unwrap(),
+            ascii.parse(&"max(1,5; 2,5) + 1.000").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_subscripts() {
+        let tree = AsciiParser::default().parse(&"x_i^2 + a_(n + 1)").unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            "x_{i}^{2} + a_{n + 1}"
+        );
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "xᵢ^2 + a_(n + 1)"
+        );
+        let tree = LatexParser::default().parse(&r"y_{12} z_q").unwrap();
+        assert_eq!(UnicodeFormatter::default().format(&tree), "y₁₂z_q");
+    }
+
+    #[test]
+    fn test_to_unicode() {
+        let parser = LatexParser::default();
+        let tree = parser.parse(&r"\frac{\alpha}{2} \times -x").unwrap();
+        assert_eq!(UnicodeFormatter::default().format(&tree), "α / 2 · −x");
+        let tree = parser.parse(&r"\sqrt{x_{1} + \pi} \cdot \infty").unwrap();
+        assert_eq!(UnicodeFormatter::default().format(&tree), "√(x₁ + π) · ∞");
+    }
+}
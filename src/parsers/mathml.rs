@@ -0,0 +1,734 @@
+//! Parser for MathML. Presentation MathML is the main target, but the common parts of Content
+//! MathML (`<apply>` with the arithmetic operators and elementary functions) are read as well.
+
+use crate::ast::{BinaryOp, Symbol, AST};
+use crate::delimiter::{self, DelimDir, DelimKind, Delimiter};
+use crate::formatter::Formatter;
+use crate::formatters::{latex::LatexFormatter, unicode::UnicodeFormatter};
+use crate::operators::{self, Op};
+use crate::parsers::ascii::{parse_tokens, ImplicitMultiplication, ParseError};
+use crate::parsers::token::{bar_delim, expects_operand, split_number, Locale, Prefix, Token};
+use crate::parsers::xml::{read_xml, XmlElement, XmlNode};
+use crate::symbols;
+
+/// Elements that have a structure of their own. Any other element, like `<mrow>` or `<mstyle>`, is
+/// treated as a row of its children.
+const STRUCTURE_ELEMENTS: [&str; 14] = [
+    "mfrac",
+    "msup",
+    "msub",
+    "msubsup",
+    "mover",
+    "munder",
+    "munderover",
+    "msqrt",
+    "mroot",
+    "semantics",
+    "apply",
+    "ci",
+    "csymbol",
+    "cn",
+];
+
+/// Operators that don't show up in the tree: function application, invisible times, and invisible
+/// plus are implied by juxtaposition.
+const INVISIBLE_OPERATORS: [&str; 4] = ["\u{2061}", "\u{2062}", "\u{2064}", ""];
+
+/// Elements that hold text. Text anywhere else, like the `x` in `<mrow>2 x</mrow>`, isn't valid
+/// MathML, but it's read as if it were in an `<mi>` or `<mn>`.
+const TOKEN_ELEMENTS: [&str; 10] = [
+    "mi",
+    "mn",
+    "mo",
+    "mtext",
+    "ms",
+    "ci",
+    "cn",
+    "csymbol",
+    "annotation",
+    "annotation-xml",
+];
+
+/// Scripted elements, with their base first and then the script below and the script above, where
+/// they have them.
+const SCRIPT_ELEMENTS: [(&str, bool, bool); 6] = [
+    ("msub", true, false),
+    ("msup", false, true),
+    ("msubsup", true, true),
+    ("munder", true, false),
+    ("mover", false, true),
+    ("munderover", true, true),
+];
+
+/// Looks up a predefined symbol by any of its representations, making a new one if there isn't one.
+fn lookup_symbol(text: &str) -> Symbol {
+    symbols::ALL_SYMBOLS
+        .iter()
+        .find(|sym| sym.reprs().contains(&text))
+        .cloned()
+        .unwrap_or_else(|| Symbol::from(text))
+}
+
+/// Turns a tree into a symbol, for the places where MathML allows any structure but we only allow
+/// symbols.
+fn tree_to_symbol(tree: &AST) -> Symbol {
+    match tree {
+        AST::Sym(sym) => sym.clone(),
+        _ => {
+            let unicode = UnicodeFormatter::default().format(tree);
+            Symbol::new(
+                &unicode,
+                &unicode,
+                &LatexFormatter::default().format(tree),
+                vec![],
+            )
+        }
+    }
+}
+
+/// Wraps any text that's outside of the elements that hold text in an element of its own.
+fn wrap_stray_text(elem: &mut XmlElement) {
+    if TOKEN_ELEMENTS.contains(&elem.name.as_str()) {
+        return;
+    }
+    for child in elem.children.iter_mut() {
+        match child {
+            XmlNode::Element(inner) => wrap_stray_text(inner),
+            XmlNode::Text(text) if !text.trim().is_empty() => {
+                let name = match split_number(text.trim(), &Locale::default()) {
+                    Some((_, "")) => "mn",
+                    _ => "mi",
+                };
+                *child = XmlNode::Element(XmlElement {
+                    name: name.to_string(),
+                    attrs: vec![],
+                    children: vec![XmlNode::Text(text.trim().to_string())],
+                });
+            }
+            XmlNode::Text(_) => {}
+        }
+    }
+}
+
+/// Looks up the big operator, like ∑ or lim, that an element stands for.
+fn big_op(elem: &XmlElement) -> Option<Symbol> {
+    if elem.name != "mo" && elem.name != "mi" {
+        return None;
+    }
+    let text = elem.text();
+    symbols::BIG_OPS
+        .iter()
+        .find(|op| op.reprs().contains(&text.as_str()))
+        .cloned()
+}
+
+/// Counts the primes in the text of an operator, if it's nothing but primes: `″` is two of them.
+fn prime_count(text: &str) -> Option<usize> {
+    text.chars()
+        .map(|c| match c {
+            '′' | '\'' => Some(1),
+            '″' => Some(2),
+            '‴' => Some(3),
+            _ => None,
+        })
+        .sum::<Option<usize>>()
+        .filter(|&count| count > 0)
+}
+
+/// Returns the whole part of a mixed number, if the tree is a number that can be one.
+fn whole_part(tree: &AST) -> Option<String> {
+    match tree {
+        AST::Number(num)
+            if num.repeating.is_none()
+                && num.exponent.is_none()
+                && num.mantissa.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            Some(num.mantissa.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Combines the arguments with an infix operator, letting the usual precedence rules decide how to
+/// group them.
+fn infix(op: &Op, args: Vec<AST>) -> Result<AST, ParseError> {
+    let mut tokens = vec![];
+    for (i, arg) in args.into_iter().enumerate() {
+        if i > 0 {
+            tokens.push(Token::Operator(op.clone()));
+        }
+        tokens.push(Token::Expr(arg));
+    }
+    parse_tokens(tokens, ImplicitMultiplication::default())
+}
+
+/// A parser for MathML, reading from XML text. `<math>` doesn't need to be the root element: any
+/// MathML element works.
+#[derive(Debug, Clone, Default)]
+pub struct MathMlParser {
+    /// How numbers are written.
+    pub locale: Locale,
+}
+
+impl MathMlParser {
+    /// Converts a single element into a tree.
+    pub fn element_to_ast(&self, elem: &XmlElement) -> Result<AST, ParseError> {
+        let children = elem.child_elements();
+        let nth_child = |n: usize| match children.get(n) {
+            Some(child) => self.element_to_ast(child),
+            None => Err(ParseError::MissingOperands),
+        };
+        match elem.name.as_str() {
+            "mfrac" => Ok(AST::BinaryExpr(
+                BinaryOp::Frac,
+                Box::new(nth_child(0)?),
+                Box::new(nth_child(1)?),
+            )),
+            "msup" => Ok(AST::BinaryExpr(
+                BinaryOp::Power,
+                Box::new(nth_child(0)?),
+                Box::new(nth_child(1)?),
+            )),
+            "msub" => Ok(AST::BinaryExpr(
+                BinaryOp::Subscript,
+                Box::new(nth_child(0)?),
+                Box::new(nth_child(1)?),
+            )),
+            "msubsup" => Ok(AST::BinaryExpr(
+                BinaryOp::Power,
+                Box::new(AST::BinaryExpr(
+                    BinaryOp::Subscript,
+                    Box::new(nth_child(0)?),
+                    Box::new(nth_child(1)?),
+                )),
+                Box::new(nth_child(2)?),
+            )),
+            // anything under or over something that isn't a big operator is a script like any other
+            "munder" => Ok(AST::BinaryExpr(
+                BinaryOp::Subscript,
+                Box::new(nth_child(0)?),
+                Box::new(nth_child(1)?),
+            )),
+            "mover" => Ok(AST::BinaryExpr(
+                BinaryOp::Power,
+                Box::new(nth_child(0)?),
+                Box::new(nth_child(1)?),
+            )),
+            "munderover" => Ok(AST::BinaryExpr(
+                BinaryOp::Power,
+                Box::new(AST::BinaryExpr(
+                    BinaryOp::Subscript,
+                    Box::new(nth_child(0)?),
+                    Box::new(nth_child(1)?),
+                )),
+                Box::new(nth_child(2)?),
+            )),
+            "msqrt" => Ok(AST::BinaryExpr(
+                BinaryOp::Root,
+                Box::new(AST::Number("2".into())),
+                Box::new(self.row_to_ast(&children)?),
+            )),
+            // the index comes second in MathML
+            "mroot" => Ok(AST::BinaryExpr(
+                BinaryOp::Root,
+                Box::new(nth_child(1)?),
+                Box::new(nth_child(0)?),
+            )),
+            // only the first child of semantics is the math itself: the rest are annotations
+            "semantics" => nth_child(0),
+            "apply" => self.apply_to_ast(&children),
+            "mtable" => {
+                let mut rows = vec![];
+                for row in children.iter().filter(|row| row.name == "mtr") {
+                    let cells = row
+                        .child_elements()
+                        .iter()
+                        .map(|cell| self.row_to_ast(&cell.child_elements()))
+                        .collect::<Result<Vec<AST>, ParseError>>()?;
+                    rows.push(cells);
+                }
+                Ok(AST::Matrix(rows))
+            }
+            "ci" | "csymbol" => Ok(AST::Sym(lookup_symbol(&elem.text()))),
+            "cn" => Ok(self
+                .read_number(&elem.text())
+                .unwrap_or_else(|| AST::Sym(Symbol::from(elem.text())))),
+            _ => self.row_to_ast(&[elem]),
+        }
+    }
+
+    /// Reads the text of an `<mn>` or `<cn>` as a number. There's only ever one number in there, so a
+    /// comma can't be separating a list: whatever the locale, it's read as separating groups of
+    /// digits, as in `1,000`, or otherwise as a decimal comma, as in `1,5`.
+    fn read_number(&self, text: &str) -> Option<AST> {
+        let group_comma = Locale {
+            group_sep: Some(','),
+            ..Locale::default()
+        };
+        [self.locale.clone(), group_comma, Locale::decimal_comma()]
+            .iter()
+            .find_map(|locale| match split_number(text, locale) {
+                Some((num, "")) => Some(num),
+                _ => None,
+            })
+    }
+
+    /// Converts a row of elements into a tree.
+    fn row_to_ast(&self, elems: &[&XmlElement]) -> Result<AST, ParseError> {
+        let mut tokens = vec![];
+        let mut elems = elems.iter();
+        while let Some(elem) = elems.next() {
+            self.push_tokens(elem, &mut elems, &mut tokens)?;
+        }
+        tokens.push(Token::End);
+        parse_tokens(tokens, ImplicitMultiplication::default())
+    }
+
+    /// Pushes the tokens for a single element of a row. Some elements, like the subscripted `log`,
+    /// also take the next element as their argument.
+    fn push_tokens<'a, I>(
+        &self,
+        elem: &XmlElement,
+        rest: &mut I,
+        tokens: &mut Vec<Token>,
+    ) -> Result<(), ParseError>
+    where
+        I: Iterator<Item = &'a &'a XmlElement> + Clone,
+    {
+        if let Some(scripts) = SCRIPT_ELEMENTS
+            .iter()
+            .find(|(name, _, _)| *name == elem.name)
+        {
+            if let Some(op) = elem.child_elements().first().and_then(|base| big_op(base)) {
+                return self.push_big_op(op, &elem.child_elements()[1..], *scripts, tokens);
+            }
+        }
+        match elem.name.as_str() {
+            "mi" => {
+                let text = elem.text();
+                match symbols::SPECIAL_FUNCS
+                    .values()
+                    .find(|func| func.reprs().contains(&text.as_str()))
+                {
+                    Some(func) => tokens.push(Token::Function(func.clone())),
+                    None => tokens.push(Token::Operand(lookup_symbol(&text))),
+                }
+            }
+            "mn" => tokens.push(match self.read_number(&elem.text()) {
+                Some(num) => Token::Expr(num),
+                None => Token::Operand(Symbol::from(elem.text())),
+            }),
+            "mtext" => {
+                let text = elem.text();
+                if !text.is_empty() {
+                    tokens.push(Token::Operand(Symbol::from(text)));
+                }
+            }
+            "mo" => {
+                let text = elem.text();
+                let whole = match tokens.last() {
+                    Some(Token::Expr(tree)) => whole_part(tree),
+                    _ => None,
+                };
+                // a mixed number is written with an invisible plus between the parts
+                if let (Some(whole), "\u{2064}") = (whole, text.as_str()) {
+                    if let Some(fraction) = rest.clone().next().filter(|next| next.name == "mfrac")
+                    {
+                        let parts = fraction.child_elements();
+                        if let [num, denom] = parts.as_slice() {
+                            if let (Some(num), Some(denom)) = (
+                                whole_part(&self.element_to_ast(num)?),
+                                whole_part(&self.element_to_ast(denom)?),
+                            ) {
+                                rest.next();
+                                tokens.pop();
+                                tokens.push(Token::Expr(AST::MixedNumber(Some(whole), num, denom)));
+                                return Ok(());
+                            }
+                        }
+                    }
+                }
+                // bars are matched up by what's around them, like in the text formats
+                let after = rest.clone().map(|next| next.text()).collect::<String>();
+                self.push_operator(&text, &after, tokens)
+            }
+            "mspace" | "annotation" | "annotation-xml" | "none" => {}
+            "mtable" => tokens.push(Token::Expr(self.element_to_ast(elem)?)),
+            "mfenced" => {
+                let open = elem.attr("open").unwrap_or("(");
+                let close = elem.attr("close").unwrap_or(")");
+                self.push_operator(open, "", tokens);
+                for (i, child) in elem.child_elements().into_iter().enumerate() {
+                    if i > 0 {
+                        tokens.push(Token::Operator(operators::COMMA.clone()));
+                    }
+                    tokens.push(Token::Expr(self.element_to_ast(child)?));
+                }
+                self.push_operator(close, "", tokens);
+            }
+            // scripted functions, like sin² and log₂
+            "msup" | "msub" => {
+                let children = elem.child_elements();
+                let func = children
+                    .first()
+                    .filter(|base| base.name == "mi")
+                    .map(|base| base.text())
+                    .filter(|name| symbols::SPECIAL_FUNCS.contains_key(name));
+                let script = children.get(1).map(|script| script.text());
+                match (elem.name.as_str(), func, script) {
+                    ("msup", Some(name), Some(script)) if script == "2" => {
+                        tokens.push(Token::Function(
+                            symbols::SPECIAL_FUNCS[&format!("{}^2", name)].clone(),
+                        ));
+                    }
+                    // the direction of a one-sided limit, like the + in x → 0⁺
+                    ("msup", _, Some(script))
+                        if children[1].name == "mo" && (script == "+" || script == "−") =>
+                    {
+                        tokens.push(Token::Expr(self.element_to_ast(children[0])?));
+                        tokens.push(Token::Operator(operators::POWER.clone()));
+                        tokens.push(Token::Operator(if script == "+" {
+                            operators::UNARY_PLUS.clone()
+                        } else {
+                            operators::UNARY_MINUS.clone()
+                        }));
+                    }
+                    // primes, which are written as superscripts
+                    ("msup", _, Some(script))
+                        if children[1].name == "mo" && prime_count(&script).is_some() =>
+                    {
+                        tokens.push(Token::Expr(self.element_to_ast(children[0])?));
+                        for _ in 0..prime_count(&script).unwrap_or_default() {
+                            tokens.push(Token::Operator(operators::PRIME.clone()));
+                        }
+                    }
+                    ("msup", Some(name), Some(script)) if script == "-1" || script == "−1" => {
+                        tokens.push(Token::Function(
+                            symbols::SPECIAL_FUNCS[&format!("{}^-1", name)].clone(),
+                        ));
+                    }
+                    ("msub", Some(name), Some(_)) if name == "log" => {
+                        let base = self.element_to_ast(children[1])?;
+                        // skip past the function application to the argument
+                        let arg = rest
+                            .find(|next| {
+                                !(next.name == "mo"
+                                    && INVISIBLE_OPERATORS.contains(&next.text().as_str()))
+                            })
+                            .ok_or(ParseError::MissingOperands)?;
+                        tokens.push(Token::Expr(AST::BinaryExpr(
+                            BinaryOp::Log,
+                            Box::new(base),
+                            Box::new(self.element_to_ast(arg)?),
+                        )));
+                    }
+                    _ => tokens.push(Token::Expr(self.element_to_ast(elem)?)),
+                }
+            }
+            name if STRUCTURE_ELEMENTS.contains(&name) => {
+                tokens.push(Token::Expr(self.element_to_ast(elem)?))
+            }
+            _ => {
+                let children = elem.child_elements();
+                if children.len() == 1 {
+                    // no need for an extra layer
+                    self.push_tokens(children[0], &mut children[1..].iter(), tokens)?;
+                } else {
+                    tokens.push(Token::Expr(self.row_to_ast(&children)?));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Pushes the tokens for a big operator and its bounds, the way the text formats write them:
+    /// `∑_(i = 1)^n`. The bounds are given along with which of them are there.
+    fn push_big_op(
+        &self,
+        op: Symbol,
+        scripts: &[&XmlElement],
+        (_, has_lower, has_upper): (&str, bool, bool),
+        tokens: &mut Vec<Token>,
+    ) -> Result<(), ParseError> {
+        tokens.push(Token::Prefix(Prefix::BigOp(op, None, None)));
+        let mut scripts = scripts.iter();
+        for (has_script, script_op) in [
+            (has_lower, &*operators::SUBSCRIPT),
+            (has_upper, &*operators::POWER),
+        ] {
+            if !has_script {
+                continue;
+            }
+            let script = scripts.next().ok_or(ParseError::MissingOperands)?;
+            tokens.push(Token::Operator(script_op.clone()));
+            tokens.push(Token::Delim(delimiter::LGROUP));
+            // the bound's tokens go in as they are, so that a limit can find its arrow and direction
+            let row = if script.name == "mrow" {
+                script.child_elements()
+            } else {
+                vec![*script]
+            };
+            let mut row = row.iter();
+            while let Some(elem) = row.next() {
+                self.push_tokens(elem, &mut row, tokens)?;
+            }
+            tokens.push(Token::Delim(delimiter::RGROUP));
+        }
+        Ok(())
+    }
+
+    /// Pushes the token for an operator, which might actually be a delimiter or a symbol like ∞,
+    /// given the text of the rest of the row.
+    fn push_operator(&self, text: &str, rest: &str, tokens: &mut Vec<Token>) {
+        if INVISIBLE_OPERATORS.contains(&text) {
+            return;
+        }
+        if text == "\u{2063}" {
+            tokens.push(Token::Operator(operators::COMMA.clone()));
+            return;
+        }
+        let bar = match text {
+            "|" => Some(DelimKind::Abs),
+            "‖" | "||" => Some(DelimKind::Norm),
+            _ => None,
+        };
+        if let Some(kind) = bar {
+            match bar_delim(kind, tokens, rest) {
+                Some(delim) => tokens.push(Token::Delim(delim)),
+                None => tokens.push(Token::Operator(operators::MID.clone())),
+            }
+            return;
+        }
+        if let Some(op) = symbols::BIG_OPS
+            .iter()
+            .find(|op| op.reprs().contains(&text))
+        {
+            tokens.push(Token::Prefix(Prefix::BigOp(op.clone(), None, None)));
+            return;
+        }
+        for dir in [DelimDir::Left, DelimDir::Right] {
+            if let Some(delim) = delimiter::DELIMS
+                .iter()
+                .find(|delim| delim.dir == dir && delim.get_symbol().reprs().contains(&text))
+            {
+                tokens.push(Token::Delim(Delimiter {
+                    dir,
+                    kind: delim.kind,
+                }));
+                return;
+            }
+        }
+        let curr_ops = operators::next_ops(expects_operand(tokens.last()));
+        match curr_ops.iter().find(|op| op.sym.reprs().contains(&text)) {
+            Some(op) => tokens.push(Token::Operator(op.clone())),
+            None => tokens.push(Token::Operand(lookup_symbol(text))),
+        }
+    }
+
+    /// Converts a Content MathML `<apply>` into a tree. The first child is the function or operator,
+    /// and the rest are its arguments.
+    fn apply_to_ast(&self, children: &[&XmlElement]) -> Result<AST, ParseError> {
+        let (head, args) = children.split_first().ok_or(ParseError::EmptyExpr)?;
+        // qualifiers like <logbase> and <degree> aren't arguments
+        let qualifier = |name: &str| match args.iter().find(|arg| arg.name == name) {
+            Some(qual) => qual
+                .child_elements()
+                .first()
+                .map(|inner| self.element_to_ast(inner))
+                .transpose(),
+            None => Ok(None),
+        };
+        let args = args
+            .iter()
+            .filter(|arg| !["logbase", "degree", "bvar"].contains(&arg.name.as_str()))
+            .map(|arg| self.element_to_ast(arg))
+            .collect::<Result<Vec<AST>, ParseError>>()?;
+
+        match (head.name.as_str(), args.len()) {
+            ("plus", 1) => Ok(AST::UnaryExpr(
+                crate::ast::UnaryOp::Generic(symbols::PLUS.clone()),
+                Box::new(args[0].clone()),
+            )),
+            ("minus", 1) => Ok(AST::UnaryExpr(
+                crate::ast::UnaryOp::Generic(symbols::MINUS.clone()),
+                Box::new(args[0].clone()),
+            )),
+            ("plus", _) => infix(&operators::ADD, args),
+            ("minus", _) => infix(&operators::SUB, args),
+            ("times", _) => infix(&operators::MULT, args),
+            ("divide", 2) => Ok(AST::BinaryExpr(
+                BinaryOp::Frac,
+                Box::new(args[0].clone()),
+                Box::new(args[1].clone()),
+            )),
+            ("power", 2) => Ok(AST::BinaryExpr(
+                BinaryOp::Power,
+                Box::new(args[0].clone()),
+                Box::new(args[1].clone()),
+            )),
+            ("root", 1) => Ok(AST::BinaryExpr(
+                BinaryOp::Root,
+                Box::new(qualifier("degree")?.unwrap_or_else(|| AST::Number("2".into()))),
+                Box::new(args[0].clone()),
+            )),
+            ("log", 1) => match qualifier("logbase")? {
+                None => Ok(AST::Function(symbols::SPECIAL_FUNCS["log"].clone(), args)),
+                Some(base) => Ok(AST::BinaryExpr(
+                    BinaryOp::Log,
+                    Box::new(base),
+                    Box::new(args[0].clone()),
+                )),
+            },
+            (name, _) => match symbols::SPECIAL_FUNCS.get(name) {
+                Some(func) => Ok(AST::Function(func.clone(), args)),
+                // a user-defined function, like <apply><ci>f</ci><ci>x</ci></apply>
+                None => Ok(AST::Function(
+                    tree_to_symbol(&self.element_to_ast(head)?),
+                    args,
+                )),
+            },
+        }
+    }
+}
+
+impl<T> super::ASTParser<T> for MathMlParser
+where
+    T: ToString,
+{
+    type ParseError = ParseError;
+
+    fn parse(&self, input: &T) -> Result<AST, Self::ParseError> {
+        let mut root = read_xml(&input.to_string())?;
+        wrap_stray_text(&mut root);
+        self.element_to_ast(&root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatters::mathml::MathMlFormatter;
+    use crate::parsers::{ASTParser, AsciiParser};
+
+    #[test]
+    fn test_presentation() {
+        let parser = MathMlParser::default();
+        let tree = parser
+            .parse(
+                &"<math><mfrac><mn>1</mn><msqrt><mi>x</mi><mo>+</mo><mn>1</mn></msqrt></mfrac>\
+                  <mo>&minus;</mo><msup><mi>sin</mi><mn>2</mn></msup><mo>&ApplyFunction;</mo>\
+                  <mi>&#x3B8;</mi></math>",
+            )
+            .unwrap();
+        assert_eq!(
+            LatexFormatter::default().format(&tree),
+            r"\frac{ 1 }{ \sqrt{x + 1} } - \sin^2\left(\theta\right)"
+        );
+        let tree = parser
+            .parse(&"<mrow><msub><mi>log</mi><mn>2</mn></msub><mo>&af;</mo><mi>x</mi></mrow>")
+            .unwrap();
+        assert_eq!(
+            UnicodeFormatter::default().format(&tree),
+            "log₂ x".to_string()
+        );
+    }
+
+    #[test]
+    fn test_rows() {
+        let parser = MathMlParser::default();
+        let ascii = AsciiParser::default();
+        assert_eq!(
+            parser
+                .parse(&"<mrow><mi>f</mi><mo>&af;</mo><mo>(</mo>x<mo>)</mo></mrow>")
+                .unwrap(),
+            ascii.parse(&"f(x)").unwrap()
+        );
+        assert_eq!(
+            parser
+                .parse(&"<mrow><mo>|</mo><mi>x</mi><mo>|</mo><mo>&InvisibleTimes;</mo><mi>y</mi></mrow>")
+                .unwrap(),
+            ascii.parse(&"|x| y").unwrap()
+        );
+        assert_eq!(
+            parser
+                .parse(&"<mrow><mo>{</mo><mi>x</mi><mo>|</mo><mi>x</mi><mo>&gt;</mo><mn>0</mn><mo>}</mo></mrow>")
+                .unwrap(),
+            ascii.parse(&"{x | x > 0}").unwrap()
+        );
+        // an invisible plus only makes a mixed number out of whole numbers
+        assert_eq!(
+            parser
+                .parse(
+                    &"<mrow><mn>2</mn><mo>&#x2064;</mo><mfrac><mi>a</mi><mn>2</mn></mfrac></mrow>"
+                )
+                .unwrap(),
+            ascii.parse(&"2 (a / 2)").unwrap().without_groups()
+        );
+        assert_eq!(
+            parser
+                .parse(&"<munder><mi>x</mi><mo>_</mo></munder>")
+                .unwrap(),
+            AST::BinaryExpr(
+                BinaryOp::Subscript,
+                Box::new(AST::Sym("x".into())),
+                Box::new(AST::Sym("_".into()))
+            )
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        let parser = MathMlParser::default();
+        let num = |digits: &str| AST::Number(digits.into());
+        assert_eq!(parser.parse(&"<mn>1,5</mn>").unwrap(), num("1.5"));
+        assert_eq!(parser.parse(&"<mn>1,000</mn>").unwrap(), num("1000"));
+        assert_eq!(parser.parse(&"<cn>1.000,5</cn>").unwrap(), num("1000.5"));
+        let european = MathMlParser {
+            locale: Locale::decimal_comma(),
+        };
+        assert_eq!(european.parse(&"<mn>1,000</mn>").unwrap(), num("1.000"));
+        assert_eq!(
+            parser.parse(&"<mn>1,5x</mn>").unwrap(),
+            AST::Sym("1,5x".into())
+        );
+    }
+
+    #[test]
+    fn test_content() {
+        let parser = MathMlParser::default();
+        let tree = parser
+            .parse(
+                &"<math><apply><plus/><apply><power/><ci>x</ci><cn>2</cn></apply>\
+                  <apply><sin/><ci>pi</ci></apply><cn>1</cn></apply></math>",
+            )
+            .unwrap();
+        assert_eq!(
+            tree,
+            AsciiParser::default().parse(&"x^2 + sin(pi) + 1").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let ascii = AsciiParser::default();
+        for input in [
+            "2 / (sin mu + 1)",
+            "(a + b) * c ^ (2 - x)",
+            "-x + max(1, 2, y)",
+            "cos^2(A) + a b c",
+            "{x in RR | x > 0} cup {1, 2}",
+            "floor(x / 2) + ceil(y) <<a, b>>",
+            "[[1, x^2], [-y, 4]] [a; b]",
+            "|x| + ||v|| - |a - |b||",
+            "sum_(i=1)^n i",
+            "lim_(x->0^+) x + int_0^1 x dx",
+            "n! + x'",
+            "2 1/2",
+            "f(x)",
+        ] {
+            let tree = ascii.parse(&input).unwrap();
+            let mathml = MathMlFormatter::default().format_math(&tree);
+            assert_eq!(MathMlParser::default().parse(&mathml).unwrap(), tree);
+        }
+    }
+}
@@ -2,9 +2,19 @@
 
 use crate::ast::AST;
 pub mod ascii;
+pub mod latex;
+pub mod mathml;
 pub mod token;
+pub mod xml;
 
 pub use ascii::AsciiParser;
+pub use latex::LatexParser;
+pub use mathml::MathMlParser;
+
+/// A parser for Unicode math, like `x² + √(y₁) · π/2`. Every `unicode_repr` is also accepted as
+/// input, and the tokenizer reads Unicode superscripts and subscripts, so this is the same parser as
+/// the ASCII one: plain text and Unicode can be mixed freely.
+pub type UnicodeParser = AsciiParser;
 
 /// Code that can parse ASTs from a given input type.
 pub trait ASTParser<I> {
@@ -3,24 +3,36 @@
 use std::fmt::Display;
 
 use crate::{
-    ast::Symbol,
+    ast::{DerivativeKind, Direction, Number, Symbol, AST},
     delimiter::{self, DelimDir, DelimKind, Delimiter},
+    formatter::Formatter,
+    formatters::unicode::UnicodeFormatter,
     operators::{self, Op},
     symbols,
 };
 
+/// The words that give the condition of a piecewise case, which the tokenizer has to keep whole.
+pub(crate) const CASE_WORDS: [&str; 2] = ["if", "otherwise"];
+
 /// A token in a math expression.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Token {
     /// A generic operand, written as a symbol.
     Operand(Symbol),
 
+    /// An operand that has already been parsed into a tree, for input formats like LaTeX where
+    /// some structures (`\frac{a}{b}`) are self-contained.
+    Expr(AST),
+
     /// An operator, written as a symbol and with given left and right precedence.
     Operator(Op),
 
     /// A function with a specific name.
     Function(Symbol),
 
+    /// An operator like a sum that applies to everything after it, up to a looser operator.
+    Prefix(Prefix),
+
     /// A delimiter.
     Delim(Delimiter),
 
@@ -32,49 +44,676 @@ impl Display for Token {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Token::Operand(sym) => write!(f, "{}", sym.unicode_repr),
+            Token::Expr(tree) => write!(f, "{}", UnicodeFormatter::default().format(tree)),
             Token::Operator(op) => write!(f, "{}", op.sym.unicode_repr),
             Token::Function(sym) => write!(f, "{}", sym.unicode_repr),
+            Token::Prefix(prefix) => write!(f, "{}", prefix),
             Token::Delim(delimiter) => write!(f, "{}", delimiter),
-            Token::End => write!(f, "{}", "eof"),
+            Token::End => write!(f, "eof"),
+        }
+    }
+}
+
+/// Returns whether the token after `last` must be an operand (or something that starts one, like a
+/// function or a left delimiter), in which case any operators must be unary. This is true at the
+/// start, after an operator, after a left delimiter, and after a function: `sin *6` makes no
+/// sense, and `sin -6` must mean sine of negative 6. After an operand or a right delimiter, it's the
+/// reverse: `12-34` must mean 12 minus 34, because having two numbers juxtaposed isn't allowed.
+pub fn expects_operand(last: Option<&Token>) -> bool {
+    match last {
+        Some(Token::Operand(_)) | Some(Token::Expr(_)) => false,
+        // postfix operators finish off an operand: f' x is f' times x
+        Some(Token::Operator(op)) if operators::POSTFIX_OPS.contains(op) => false,
+        Some(Token::Delim(Delimiter { dir, kind: _ })) => dir == &DelimDir::Left,
+        _ => true,
+    }
+}
+
+/// Works out which delimiter a bar of the given kind is, like the ones in |x| or ‖v‖, from the
+/// tokens before it and the input after it. This works a lot like telling unary and binary
+/// operators apart: where an operand is expected, the bar opens, and otherwise it closes the
+/// innermost bar of its kind that's still open. If there isn't one, the bar opens a new pair next to
+/// what came before, as in 2|x|, as long as there's another bar later to close it. Otherwise, or if
+/// it's the first bar in a set, like {x | x > 0}, it isn't a delimiter at all: that's when this
+/// returns None.
+pub fn bar_delim(kind: DelimKind, tokens: &[Token], rest: &str) -> Option<Delimiter> {
+    let open = |dir| Some(Delimiter { dir, kind });
+    if expects_operand(tokens.last()) {
+        return open(DelimDir::Left);
+    }
+    // the delimiters that are still open, along with whether a set-builder bar came after them
+    let mut unclosed = vec![];
+    for token in tokens {
+        match token {
+            Token::Delim(Delimiter {
+                dir: DelimDir::Left,
+                kind,
+            }) => unclosed.push((*kind, false)),
+            Token::Delim(_) => {
+                unclosed.pop();
+            }
+            Token::Operator(op) if op == &*operators::MID => {
+                if let Some((_, mid)) = unclosed.last_mut() {
+                    *mid = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    match unclosed.last() {
+        Some((inner, _)) if inner == &kind => open(DelimDir::Right),
+        Some((DelimKind::Brace, false)) if kind == DelimKind::Abs => None,
+        _ if rest.contains(['|', '‖']) => open(DelimDir::Left),
+        _ => None,
+    }
+}
+
+/// Returns whether the next bar in the input is a double one, `||` or `‖`.
+fn next_bar_is_double(input: &str) -> bool {
+    input
+        .find(['|', '‖'])
+        .is_some_and(|i| input[i..].starts_with("||") || input[i..].starts_with('‖'))
+}
+
+/// Splits a run of ASCII digits off the front of the input.
+fn split_digits(input: &str) -> (&str, &str) {
+    let len = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    input.split_at(len)
+}
+
+/// Splits an integer exponent with an optional sign off the front of the input, like the `-3` in
+/// `1.2e-3`. A negative exponent always comes back with an ASCII `-`.
+fn split_exponent(input: &str) -> Option<(String, &str)> {
+    let (sign, rest) = match input.chars().next() {
+        Some(c @ ('-' | '−')) => ("-", &input[c.len_utf8()..]),
+        Some('+') => ("", &input[1..]),
+        _ => ("", input),
+    };
+    let (digits, rest) = split_digits(rest);
+    if digits.is_empty() {
+        return None;
+    }
+    Some((format!("{}{}", sign, digits), rest))
+}
+
+/// Splits the power of ten in a number like `1.2 × 10^-3` off the front of the input, returning the
+/// exponent and the rest. The times sign has to be one that can't be a variable, so `3 x 10^2` is a
+/// product with `x` in it. The power can be written with a caret, with the exponent in parentheses
+/// or braces or not, or with superscripts, like `10⁻³`.
+fn split_power_of_ten(input: &str) -> Option<(String, &str)> {
+    let rest = input.trim_start();
+    let rest = ["*", "×", r"\times"]
+        .iter()
+        .find_map(|times| rest.strip_prefix(times))?;
+    let rest = rest.trim_start().strip_prefix("10")?;
+    let script_len: usize = rest
+        .chars()
+        .take_while(|c| symbols::from_superscript(*c).is_some())
+        .map(char::len_utf8)
+        .sum();
+    if script_len > 0 {
+        let script: String = rest[..script_len]
+            .chars()
+            .filter_map(symbols::from_superscript)
+            .collect();
+        return match split_exponent(&script) {
+            Some((exponent, "")) => Some((exponent, &rest[script_len..])),
+            _ => None,
+        };
+    }
+    let rest = rest.trim_start().strip_prefix('^')?.trim_start();
+    for (open, close) in [('(', ')'), ('{', '}')] {
+        if let Some(inside) = rest.strip_prefix(open) {
+            let (exponent, after) = split_exponent(inside.trim_start())?;
+            return Some((exponent, after.trim_start().strip_prefix(close)?));
+        }
+    }
+    split_exponent(rest)
+}
+
+/// Splits the whole part of a number off the front of the input, leaving out the separators between
+/// groups of digits: with `,` as the separator, `1,000,000` is `1000000`. A separator only counts
+/// when three digits come right after it, so `1,5` stops before the comma.
+fn split_integer(input: &str, group_sep: Option<char>) -> (String, &str) {
+    let (first, mut rest) = split_digits(input);
+    let mut integer = first.to_string();
+    let Some(sep) = group_sep.filter(|_| (1..=3).contains(&first.len())) else {
+        return (integer, rest);
+    };
+    while let Some((group, after)) = rest.strip_prefix(sep).map(split_digits) {
+        if group.len() != 3 {
+            break;
+        }
+        integer.push_str(group);
+        rest = after;
+    }
+    (integer, rest)
+}
+
+/// Splits digits written as superscripts or subscripts off the front of the input, given the
+/// function that reads the script, returning them as normal digits along with the rest.
+fn split_script_digits(input: &str, from_script: fn(char) -> Option<char>) -> (String, &str) {
+    let len: usize = input
+        .chars()
+        .take_while(|c| from_script(*c).is_some_and(|c| c.is_ascii_digit()))
+        .map(char::len_utf8)
+        .sum();
+    let digits = input[..len].chars().filter_map(from_script).collect();
+    (digits, &input[len..])
+}
+
+/// Splits a vulgar fraction off the front of the input, returning its numerator, its denominator,
+/// and the rest. It can be a single character, like `½`, or superscript digits over subscript
+/// digits with a fraction slash in between, like `⁵⁄₇`.
+fn split_vulgar_fraction(input: &str) -> Option<(String, String, &str)> {
+    let c = input.chars().next()?;
+    if let Some((numerator, denominator)) = symbols::from_vulgar_fraction(c) {
+        return Some((numerator.into(), denominator.into(), &input[c.len_utf8()..]));
+    }
+    let (numerator, rest) = split_script_digits(input, symbols::from_superscript);
+    let rest = rest.strip_prefix('⁄')?;
+    let (denominator, rest) = split_script_digits(rest, symbols::from_subscript);
+    if numerator.is_empty() || denominator.is_empty() {
+        return None;
+    }
+    Some((numerator, denominator, rest))
+}
+
+/// Splits the fraction in a mixed number like `1 1/2` off the front of the input, after the whole
+/// part, returning its numerator, its denominator, and the rest. The fraction has to be proper and
+/// have no spaces in it: `2 3/2` and `2 1 / 2` are products.
+fn split_mixed_fraction(input: &str) -> Option<(String, String, &str)> {
+    let rest = input.trim_start_matches(' ');
+    if rest.len() == input.len() {
+        return None;
+    }
+    let (numerator, rest) = split_digits(rest);
+    let (denominator, rest) = split_digits(rest.strip_prefix('/')?);
+    match (numerator.parse::<u64>(), denominator.parse::<u64>()) {
+        (Ok(n), Ok(d)) if n < d => Some((numerator.into(), denominator.into(), rest)),
+        _ => None,
+    }
+}
+
+/// Finds the digits repeating at the end of the digits after a decimal point, for decimals written
+/// with an ellipsis: the digits that show up at least twice in a row, like the `6` in `0.1666...`
+/// or the `12` in `0.1212...`. Returns the digits before the repetition and the digits that repeat,
+/// or None if there's no repetition to see, as in `3.14159...` or `3.1...`, which are just cut off.
+fn repeating_block(digits: &str) -> Option<(&str, &str)> {
+    let len = (1..=digits.len() / 2).find(|&len| {
+        let (before, block) = digits.split_at(digits.len() - len);
+        before.ends_with(block)
+    })?;
+    let block = &digits[digits.len() - len..];
+    let mut before = digits;
+    while let Some(rest) = before.strip_suffix(block) {
+        before = rest;
+    }
+    Some((before, block))
+}
+
+/// Splits the digits after a decimal point off the front of the input, returning the digits, the
+/// digits that repeat after them if it's a repeating decimal, and the rest. Repeating digits can be
+/// written in parentheses, as in `0.1(6)`, with an overline on each one, as in `0.16̅`, or as
+/// digits that repeat before an ellipsis, as in `0.1666...`.
+fn split_fraction(input: &str) -> (String, Option<String>, &str) {
+    const OVERLINE: char = '\u{305}';
+    let mut digits = String::new();
+    let mut overlined = String::new();
+    let mut rest = input;
+    while let Some(c) = rest.chars().next().filter(char::is_ascii_digit) {
+        match rest[1..].strip_prefix(OVERLINE) {
+            Some(after) => {
+                overlined.push(c);
+                rest = after;
+            }
+            None if overlined.is_empty() => {
+                digits.push(c);
+                rest = &rest[1..];
+            }
+            None => break,
+        }
+    }
+    if !overlined.is_empty() {
+        return (digits, Some(overlined), rest);
+    }
+    if let Some(inside) = rest.strip_prefix('(') {
+        let (repeating, after) = split_digits(inside);
+        if let Some(after) = after.strip_prefix(')').filter(|_| !repeating.is_empty()) {
+            return (digits, Some(repeating.into()), after);
+        }
+    }
+    if let Some(after) = rest.strip_prefix("...").or_else(|| rest.strip_prefix('…')) {
+        if let Some((before, repeating)) = repeating_block(&digits) {
+            return (before.into(), Some(repeating.into()), after);
+        }
+    }
+    (digits, None, rest)
+}
+
+/// Splits a number literal off the front of the input, returning it and the rest: an integer or a
+/// decimal like `12` or `.5`, which can go on to be in scientific notation, as in `1.2e-3`,
+/// `6.02E23`, or `1.2 × 10^3`, or to repeat, as in `0.(3)`. Mixed numbers like `1 1/2` or `1½` and
+/// vulgar fractions like `½` count too. The locale says how the decimal point and any separators
+/// between groups of digits are written. Returns None if the input doesn't start with a number.
+pub fn split_number<'a>(input: &'a str, locale: &Locale) -> Option<(AST, &'a str)> {
+    split_literal(input, locale, true)
+}
+
+/// Splits a number literal off the front of the input like `split_number`, but only reads a mixed
+/// number if `mixed` is set. Scripts don't take mixed numbers: `x^2 1/3` isn't `x^(2 1/3)`.
+fn split_literal<'a>(input: &'a str, locale: &Locale, mixed: bool) -> Option<(AST, &'a str)> {
+    if let Some((numerator, denominator, rest)) = split_vulgar_fraction(input) {
+        return Some((AST::MixedNumber(None, numerator, denominator), rest));
+    }
+    let (integer, rest) = split_integer(input, locale.group_sep);
+    if mixed && !integer.is_empty() {
+        if let Some((numerator, denominator, rest)) =
+            split_vulgar_fraction(rest).or_else(|| split_mixed_fraction(rest))
+        {
+            return Some((
+                AST::MixedNumber(Some(integer), numerator, denominator),
+                rest,
+            ));
+        }
+    }
+    // the decimal point only counts if there are digits after it
+    let (fraction, repeating, rest) =
+        match rest.strip_prefix(locale.decimal_sep).map(split_fraction) {
+            Some((fraction, repeating, after)) if !fraction.is_empty() || repeating.is_some() => {
+                (fraction, repeating, after)
+            }
+            _ => (String::new(), None, rest),
+        };
+    if integer.is_empty() && fraction.is_empty() && repeating.is_none() {
+        return None;
+    }
+    // repeating decimals always have the point, as in 0.(3)
+    let mantissa = if fraction.is_empty() && repeating.is_none() {
+        integer
+    } else {
+        format!("{}.{}", integer, fraction)
+    };
+    if let Some(repeating) = repeating {
+        return Some((AST::Number(Number::repeating(&mantissa, &repeating)), rest));
+    }
+    let exponent = rest
+        .strip_prefix(['e', 'E'])
+        .and_then(split_exponent)
+        .or_else(|| split_power_of_ten(rest));
+    let num = match &exponent {
+        Some((exponent, _)) => Number::scientific(&mantissa, exponent),
+        None => Number::from(mantissa.as_str()),
+    };
+    Some((AST::Number(num), exponent.map_or(rest, |(_, after)| after)))
+}
+
+/// An operator that applies to the expression after it, like a sum or a limit. These are parsed
+/// like unary operators, but they carry more information than just a symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Prefix {
+    /// A big operator, like a sum, with its lower and upper bounds. Tokenizers leave the bounds
+    /// empty: they're filled in from the scripts after the operator before parsing.
+    BigOp(Symbol, Option<Box<AST>>, Option<Box<AST>>),
+    /// A limit with its variable, target, and direction. This comes from a `lim` big operator once
+    /// its subscript is known.
+    Limit(Box<AST>, Box<AST>, Option<Direction>),
+    /// A derivative operator like `d/dx`, with its kind, order, and variable.
+    Derivative(DerivativeKind, u32, Box<AST>),
+    /// A quantifier like `forall x,` with the variable it binds. Tokenizers write quantifiers as
+    /// plain symbols: they become prefixes once the binding after them is known.
+    Quantifier(Symbol, Box<AST>),
+}
+
+impl Prefix {
+    /// Applies the operator to its body.
+    pub fn apply(self, body: AST) -> AST {
+        match self {
+            Prefix::BigOp(op, lower, upper) => AST::BigOp(op, lower, upper, Box::new(body)),
+            Prefix::Limit(var, target, direction) => {
+                AST::Limit(var, target, direction, Box::new(body))
+            }
+            // d/dx works like a function, so the parentheses in d/dx (x^2) only mark its argument
+            Prefix::Derivative(kind, order, var) => {
+                AST::Derivative(kind, order, Box::new(body.ungroup_parens()), var)
+            }
+            Prefix::Quantifier(sym, binding) => AST::Quantifier(sym, binding, Box::new(body)),
+        }
+    }
+
+    /// The right precedence of the operator, which controls how much of what comes after it is its
+    /// body.
+    pub fn r_prec(&self) -> Option<u8> {
+        match self {
+            Prefix::Quantifier(_, _) => operators::QUANTIFIER.r_prec,
+            _ => operators::BIG_OP.r_prec,
+        }
+    }
+}
+
+impl Display for Prefix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Prefix::BigOp(sym, _, _) => write!(f, "{}", sym.unicode_repr),
+            Prefix::Limit(_, _, _) => write!(f, "{}", symbols::LIM.unicode_repr),
+            Prefix::Derivative(_, _, var) => {
+                write!(f, "d/d{}", UnicodeFormatter::default().format(var))
+            }
+            Prefix::Quantifier(sym, binding) => write!(
+                f,
+                "{}{},",
+                sym.unicode_repr,
+                UnicodeFormatter::default().format(binding)
+            ),
+        }
+    }
+}
+
+/// The way numbers and lists are written, which differs between languages: `3.14` and `f(a, b)` in
+/// English, but `3,14` and `f(a; b)` in much of Europe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    /// The decimal point, like the `.` in `3.14`. A decimal comma only counts with a digit right
+    /// after it, so with one `f(1,5)` has the single argument `1.5`, but `f(1, 5)` has two.
+    pub decimal_sep: char,
+    /// The separator between groups of three digits, like the `,` in `1,000,000`, if numbers can
+    /// have one.
+    pub group_sep: Option<char>,
+    /// The separator between function arguments and the elements of lists, like the `,` in
+    /// `f(a, b)`. If this is anything else, it takes the place of the operator written the same
+    /// way: with `;`, matrix rows and piecewise cases can't be split with semicolons.
+    pub list_sep: char,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale {
+            decimal_sep: '.',
+            group_sep: None,
+            list_sep: ',',
+        }
+    }
+}
+
+impl Locale {
+    /// The locale for a decimal comma, as in `3,14`, with periods between groups of digits, as in
+    /// `1.000.000`, and semicolons between arguments, as in `f(a; b)`.
+    pub fn decimal_comma() -> Locale {
+        Locale {
+            decimal_sep: ',',
+            group_sep: Some('.'),
+            list_sep: ';',
         }
     }
 }
 
 /// A tokenizer that parses strings into a list of tokens.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
-// options TBD
-pub struct Tokenizer {}
+pub struct Tokenizer {
+    /// How numbers and lists are written.
+    pub locale: Locale,
+    /// Whether a run of letters that isn't anything known, like `xy`, is a product of single-letter
+    /// variables instead of one symbol. Functions and operators are found before this happens, so
+    /// `sinx` is still `sin x`. The `if` and `otherwise` of piecewise functions stay whole only
+    /// when they're the entire run, so `ifx` is `i f x`.
+    pub split_letters: bool,
+    /// Words that stay whole when splitting letters, like `dx` or `rate`. The longest one that fits
+    /// is taken at each point of a run, so `xdx` is `x dx` and `xrate` is `x rate`.
+    pub words: Vec<String>,
+    /// Whether digits right after letters are a subscript: `x2` is `x_2`, and `ab12` is `ab_12`.
+    pub subscript_digits: bool,
+}
 
 impl Tokenizer {
+    /// Pushes a run of characters that isn't anything known, which becomes a single operand unless
+    /// it's letters and digits that the options say to split up.
+    fn push_operand(&self, tokens: &mut Vec<Token>, word: &str) {
+        let splits = word.starts_with(char::is_alphabetic)
+            && word
+                .chars()
+                .all(|c| c.is_alphabetic() || c.is_ascii_digit());
+        if !(splits && (self.split_letters || self.subscript_digits)) {
+            tokens.push(Token::Operand(word.into()));
+            return;
+        }
+        // each piece is some letters and then maybe some digits, as in x2y3
+        let mut rest = word;
+        while !rest.is_empty() {
+            let letters_len = rest
+                .find(|c: char| !c.is_alphabetic())
+                .unwrap_or(rest.len());
+            let (letters, after) = rest.split_at(letters_len);
+            let digits_len = after
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after.len());
+            let (digits, after) = after.split_at(digits_len);
+            rest = after;
+            let mut names = vec![];
+            if !self.split_letters || CASE_WORDS.contains(&letters) {
+                names.push(letters.to_string());
+            } else {
+                let mut letters = letters;
+                while let Some(c) = letters.chars().next() {
+                    let len = self
+                        .words
+                        .iter()
+                        .filter(|w| !w.is_empty() && letters.starts_with(w.as_str()))
+                        .map(String::len)
+                        .max()
+                        .unwrap_or(c.len_utf8());
+                    let (name, after) = letters.split_at(len);
+                    names.push(name.to_string());
+                    letters = after;
+                }
+            }
+            if !self.subscript_digits {
+                names.last_mut().unwrap().push_str(digits);
+            }
+            tokens.extend(names.into_iter().map(|name| Token::Operand(name.into())));
+            if self.subscript_digits && !digits.is_empty() {
+                tokens.push(Token::Operator(operators::SUBSCRIPT.clone()));
+                tokens.push(Token::Expr(AST::Number(digits.into())));
+            }
+        }
+    }
+
     /// Tokenizes an expression into a list of tokens.
     pub fn tokenize(&self, input: &str) -> Vec<Token> {
-        let mut rest = input.clone();
+        let mut rest = input;
         let mut tokens = vec![];
         let mut curr_unknown = String::new();
+        // for each brace that hasn't been closed yet, whether it groups a script instead of being
+        // part of a set
+        let mut braces = vec![];
+        // for each ( that hasn't been closed yet, the delimiter its ) closes
+        let mut parens = vec![];
         'parse: while !rest.is_empty() {
             // first, some cleanup to get rid of whitespace
             match rest.chars().next() {
                 Some(c) if c.is_whitespace() => {
                     // push previous unknown token onto list
                     if !curr_unknown.is_empty() {
-                        tokens.push(Token::Operand(curr_unknown.into()));
+                        self.push_operand(&mut tokens, &curr_unknown);
                         curr_unknown = String::new();
                     }
-                    rest = &rest[1..];
+                    rest = &rest[c.len_utf8()..];
+                    continue 'parse;
                 }
                 _ => {}
             };
 
+            // numbers, but not digits in the middle of a word like x2
+            if curr_unknown.is_empty() {
+                let after_script = matches!(
+                    tokens.last(),
+                    Some(Token::Operator(op))
+                        if op == &*operators::POWER || op == &*operators::SUBSCRIPT
+                );
+                if let Some((num, after)) = split_literal(rest, &self.locale, !after_script) {
+                    tokens.push(Token::Expr(num));
+                    rest = after;
+                    continue 'parse;
+                }
+            }
+
+            // a list separator other than a comma works like one
+            if self.locale.list_sep != ',' {
+                if let Some(after) = rest.strip_prefix(self.locale.list_sep) {
+                    // push previous unknown token onto list
+                    if !curr_unknown.is_empty() {
+                        self.push_operand(&mut tokens, &curr_unknown);
+                        curr_unknown = String::new();
+                    }
+                    tokens.push(Token::Operator(operators::COMMA.clone()));
+                    rest = after;
+                    continue 'parse;
+                }
+            }
+
+            // Unicode superscripts are exponents and Unicode subscripts are subscripts: x² is x^(2),
+            // and y₁ is y_(1)
+            for (from_script, op) in [
+                (
+                    symbols::from_superscript as fn(char) -> Option<char>,
+                    &*operators::POWER,
+                ),
+                (symbols::from_subscript, &*operators::SUBSCRIPT),
+            ] {
+                let script_len: usize = rest
+                    .chars()
+                    .take_while(|c| from_script(*c).is_some())
+                    .map(char::len_utf8)
+                    .sum();
+                if script_len > 0 {
+                    let script: String =
+                        rest[..script_len].chars().filter_map(from_script).collect();
+                    rest = &rest[script_len..];
+                    // push previous unknown token onto list
+                    if !curr_unknown.is_empty() {
+                        self.push_operand(&mut tokens, &curr_unknown);
+                        curr_unknown = String::new();
+                    }
+                    // a lone ᵀ after an operand is a transpose, like ^T
+                    if script == "T"
+                        && op == &*operators::POWER
+                        && !(curr_unknown.is_empty() && expects_operand(tokens.last()))
+                    {
+                        tokens.push(Token::Operator(operators::TRANSPOSE.clone()));
+                        continue 'parse;
+                    }
+                    let mut script = self.tokenize(&script);
+                    // get rid of the end-of-expression marker
+                    script.pop();
+                    tokens.push(Token::Operator(op.clone()));
+                    tokens.push(Token::Delim(delimiter::LGROUP));
+                    tokens.append(&mut script);
+                    tokens.push(Token::Delim(delimiter::RGROUP));
+                    continue 'parse;
+                }
+            }
+
+            // braces right after a script group it, like in LaTeX: x_{ij} is x_(ij), not x_({ij}).
+            // Any other braces are sets, like {1, 2}
+            let after_script = matches!(
+                tokens.last(),
+                Some(Token::Operator(op)) if op == &*operators::SUBSCRIPT || op == &*operators::POWER
+            );
+            if rest.starts_with('{') {
+                rest = &rest[1..];
+                let groups = curr_unknown.is_empty() && after_script;
+                // push previous unknown token onto list
+                if !curr_unknown.is_empty() {
+                    self.push_operand(&mut tokens, &curr_unknown);
+                    curr_unknown = String::new();
+                }
+                braces.push(groups);
+                tokens.push(Token::Delim(if groups {
+                    delimiter::LGROUP
+                } else {
+                    delimiter::LBRACE
+                }));
+                continue 'parse;
+            }
+            if rest.starts_with('}') {
+                rest = &rest[1..];
+                // push previous unknown token onto list
+                if !curr_unknown.is_empty() {
+                    self.push_operand(&mut tokens, &curr_unknown);
+                    curr_unknown = String::new();
+                }
+                tokens.push(Token::Delim(if braces.pop().unwrap_or(false) {
+                    delimiter::RGROUP
+                } else {
+                    delimiter::RBRACE
+                }));
+                continue 'parse;
+            }
+
+            // bars for absolute values and norms, which could be opening or closing. || is a norm
+            // only where one can start or end: otherwise it's an or, or the end of an absolute value
+            // followed by another bar, as in |x||y|
+            if rest.starts_with(['|', '‖']) {
+                // push previous unknown token onto list
+                if !curr_unknown.is_empty() {
+                    self.push_operand(&mut tokens, &curr_unknown);
+                    curr_unknown = String::new();
+                }
+                let bar = if let Some(after) = rest.strip_prefix('‖') {
+                    bar_delim(DelimKind::Norm, &tokens, after).map(|delim| (delim, '‖'.len_utf8()))
+                } else {
+                    // a || next to something is an or, so what comes after it doesn't matter
+                    let norm =
+                        bar_delim(DelimKind::Norm, &tokens, "").filter(|_| rest.starts_with("||"));
+                    let abs = bar_delim(DelimKind::Abs, &tokens, &rest[1..]);
+                    match (norm, abs) {
+                        // a norm that opens needs a double bar to close it, so the first two bars
+                        // of ||x| - |y|| open absolute values instead
+                        (Some(norm), _)
+                            if norm.dir == DelimDir::Right || next_bar_is_double(&rest[2..]) =>
+                        {
+                            Some((norm, 2))
+                        }
+                        (Some(_), Some(abs)) => Some((abs, 1)),
+                        (None, Some(abs))
+                            if abs.dir == DelimDir::Right || !rest.starts_with("||") =>
+                        {
+                            Some((abs, 1))
+                        }
+                        _ => None,
+                    }
+                };
+                // anything else is an operator, like the bar in a set
+                if let Some((delim, len)) = bar {
+                    rest = &rest[len..];
+                    tokens.push(Token::Delim(delim));
+                    continue 'parse;
+                }
+            }
+
             // match delimiters
             for delim in delimiter::DELIMS.iter() {
                 if let Some(repr) = delim.get_symbol().match_front(rest) {
                     rest = &rest[repr.len()..];
                     // push previous unknown token onto list
                     if !curr_unknown.is_empty() {
-                        tokens.push(Token::Operand(curr_unknown.into()));
+                        self.push_operand(&mut tokens, &curr_unknown);
                         curr_unknown = String::new();
                     }
-                    tokens.push(Token::Delim(*delim));
+                    // floor( and ceil( are closed by a plain ), so keep track of what each ( is
+                    let delim = if repr.ends_with('(') {
+                        parens.push(Delimiter {
+                            dir: DelimDir::Right,
+                            kind: delim.kind,
+                        });
+                        *delim
+                    } else if repr == ")" {
+                        parens.pop().unwrap_or(*delim)
+                    } else {
+                        *delim
+                    };
+                    tokens.push(Token::Delim(delim));
                     // continue outer parsing loop
                     continue 'parse;
                 }
@@ -83,13 +722,19 @@ impl Tokenizer {
             // now match known functions
             // iterate in reverse alphabetical order. This
             // means, for ties like cos^2 and cos, the longer one
-            // goes first.
-            for (_name, sym) in symbols::SPECIAL_FUNCS.iter().rev() {
+            // goes first. Roots like √ and matrices like mat(a, b; c, d) are written as functions
+            // too.
+            for sym in symbols::SPECIAL_FUNCS
+                .values()
+                .rev()
+                .chain(symbols::ROOTS.iter())
+                .chain([&*symbols::MATRIX])
+            {
                 if let Some(repr) = sym.match_front(rest) {
                     rest = &rest[repr.len()..];
                     // push previous unknown token onto list
                     if !curr_unknown.is_empty() {
-                        tokens.push(Token::Operand(curr_unknown.into()));
+                        self.push_operand(&mut tokens, &curr_unknown);
                         curr_unknown = String::new();
                     }
                     tokens.push(Token::Function(sym.clone()));
@@ -98,57 +743,78 @@ impl Tokenizer {
                 }
             }
 
+            // big operators, which get their bounds later
+            for sym in symbols::BIG_OPS.iter() {
+                if let Some(repr) = sym.match_front(rest) {
+                    rest = &rest[repr.len()..];
+                    // push previous unknown token onto list
+                    if !curr_unknown.is_empty() {
+                        self.push_operand(&mut tokens, &curr_unknown);
+                        curr_unknown = String::new();
+                    }
+                    tokens.push(Token::Prefix(Prefix::BigOp(sym.clone(), None, None)));
+                    // continue outer parsing loop
+                    continue 'parse;
+                }
+            }
+
             // This part is very thorny: we need to handle unary plus/minus operators correctly. The
             // weird thing is that this depends on the state of the parsing so far: specifically,
-            // the last token matched. If it's the start, an operator, a left delimiter, or a
-            // function, then unary operators are the only allowed operators (`sin *6` makes no
-            // sense, and `sin -6` must mean sine of negative 6). If it's an operand or right
-            // delimiter, then it's the reverse: `12-34` must mean 12 minus 34, because having two
-            // numbers juxtaposed isn't allowed.
-            let curr_ops = match tokens.last() {
-                Some(Token::Operand(_)) => operators::BINARY_OPS.clone(),
-                Some(Token::Delim(Delimiter { dir, kind: _ })) if dir == &DelimDir::Right => {
-                    operators::BINARY_OPS.clone()
-                }
-                _ => {
-                    // If there's an unrecognized symbol being built up, then we can't search for
-                    // unary operators: if we're in the middle of a-b, we should realize that - is a
-                    // binary operator
-                    if curr_unknown.is_empty() {
-                        operators::UNARY_OPS.clone()
-                    } else {
-                        operators::BINARY_OPS.clone()
+            // the last token matched. See `expects_operand` for the details. If there's an
+            // unrecognized symbol being built up, then we can't search for unary operators: if
+            // we're in the middle of a-b, we should realize that - is a binary operator.
+            let mut curr_ops =
+                operators::next_ops(curr_unknown.is_empty() && expects_operand(tokens.last()));
+            // functions can take subscripts too, like log_2, and big operators take both scripts
+            if curr_unknown.is_empty() {
+                match tokens.last() {
+                    Some(Token::Function(_)) => curr_ops.push(operators::SUBSCRIPT.clone()),
+                    Some(Token::Prefix(_)) => {
+                        curr_ops.push(operators::SUBSCRIPT.clone());
+                        curr_ops.push(operators::POWER.clone());
                     }
+                    _ => {}
                 }
-            };
+            }
 
-            // match operators next: they tend not to conflict with other
-            // things, and the bigger words will get mangled by future
-            // transformations
-            for op in curr_ops.iter() {
-                println!(
-                    "{} | {} | [{}]",
-                    &op.sym.unicode_repr,
-                    rest.clone(),
-                    tokens
-                        .iter()
-                        .map(|x| x.to_string())
-                        .collect::<Vec<String>>()
-                        .join(", ")
-                );
-                if let Some(repr) = dbg!(op.match_front(rest)) {
-                    rest = &rest[repr.len()..];
+            // ″ and ‴ are several primes written as one character
+            if curr_ops.contains(&operators::PRIME) {
+                if let Some((c, count)) = [('″', 2), ('‴', 3)]
+                    .into_iter()
+                    .find(|(c, _)| rest.starts_with(*c))
+                {
+                    rest = &rest[c.len_utf8()..];
                     // push previous unknown token onto list
                     if !curr_unknown.is_empty() {
-                        tokens.push(Token::Operand(curr_unknown.into()));
+                        self.push_operand(&mut tokens, &curr_unknown);
                         curr_unknown = String::new();
                     }
-                    tokens.push(Token::Operator(op.clone()));
-                    // continue outer parsing loop
+                    for _ in 0..count {
+                        tokens.push(Token::Operator(operators::PRIME.clone()));
+                    }
                     continue 'parse;
                 }
             }
 
+            // match operators next: they tend not to conflict with other
+            // things, and the bigger words will get mangled by future
+            // transformations
+            // operators written as words, like deg, can't start in the middle of a word either
+            let in_word = curr_unknown.ends_with(char::is_alphabetic);
+            if let Some((op, repr)) = operators::longest_match(&curr_ops, rest)
+                .filter(|(_, repr)| !(in_word && repr.starts_with(char::is_alphabetic)))
+            {
+                rest = &rest[repr.len()..];
+                // push previous unknown token onto list
+                if !curr_unknown.is_empty() {
+                    self.push_operand(&mut tokens, &curr_unknown);
+                    curr_unknown = String::new();
+                }
+                tokens.push(Token::Operator(op.clone()));
+                // continue outer parsing loop
+                continue 'parse;
+            }
+
             // now match known non-Latin letter symbols
             for sym in symbols::ALL_SYMBOLS.iter() {
                 if !symbols::LATIN_SYMBOLS.contains_key(&sym.ascii_repr) {
@@ -156,7 +822,7 @@ impl Tokenizer {
                         rest = &rest[repr.len()..];
                         // push previous unknown token onto list
                         if !curr_unknown.is_empty() {
-                            tokens.push(Token::Operand(curr_unknown.into()));
+                            self.push_operand(&mut tokens, &curr_unknown);
                             curr_unknown = String::new();
                         }
                         tokens.push(Token::Operand(sym.clone()));
@@ -167,14 +833,15 @@ impl Tokenizer {
             }
 
             // if unknown, add to current unknown symbol
-            curr_unknown.push(rest.chars().next().unwrap().into());
-            rest = &rest[1..];
+            let c = rest.chars().next().unwrap();
+            curr_unknown.push(c);
+            rest = &rest[c.len_utf8()..];
         }
 
         // add end of expression symbol
         // push previous unknown token onto list
         if !curr_unknown.is_empty() {
-            tokens.push(Token::Operand(curr_unknown.into()));
+            self.push_operand(&mut tokens, &curr_unknown);
         }
         tokens.push(Token::End);
         tokens
@@ -193,11 +860,358 @@ mod tests {
         assert_eq!(
             expr,
             vec![
-                Token::Operand(Symbol::from("1")),
+                Token::Expr(AST::Number("1".into())),
                 Token::Operator(PM.clone()),
-                Token::Operand(Symbol::from("2")),
+                Token::Expr(AST::Number("2".into())),
                 Token::End
             ]
         )
     }
+
+    #[test]
+    fn test_unicode_scripts() {
+        let tokenizer = Tokenizer::default();
+        assert_eq!(
+            tokenizer.tokenize("x²"),
+            vec![
+                Token::Operand(Symbol::from("x")),
+                Token::Operator(operators::POWER.clone()),
+                Token::Delim(delimiter::LGROUP),
+                Token::Expr(AST::Number("2".into())),
+                Token::Delim(delimiter::RGROUP),
+                Token::End
+            ]
+        );
+        assert_eq!(
+            tokenizer.tokenize("aᵢⱼ"),
+            vec![
+                Token::Operand(Symbol::from("a")),
+                Token::Operator(operators::SUBSCRIPT.clone()),
+                Token::Delim(delimiter::LGROUP),
+                Token::Operand(Symbol::from("ij")),
+                Token::Delim(delimiter::RGROUP),
+                Token::End
+            ]
+        );
+        // unknown non-ASCII characters are fine
+        assert_eq!(
+            tokenizer.tokenize("ℵ₀"),
+            vec![
+                Token::Operand(Symbol::from("ℵ")),
+                Token::Operator(operators::SUBSCRIPT.clone()),
+                Token::Delim(delimiter::LGROUP),
+                Token::Expr(AST::Number("0".into())),
+                Token::Delim(delimiter::RGROUP),
+                Token::End
+            ]
+        );
+        // braces group ASCII scripts
+        assert_eq!(tokenizer.tokenize("a_{ij}"), tokenizer.tokenize("aᵢⱼ"));
+    }
+
+    #[test]
+    fn test_numbers() {
+        let tokenizer = Tokenizer::default();
+        let num = |num: Number| vec![Token::Expr(AST::Number(num)), Token::End];
+        assert_eq!(tokenizer.tokenize("12.5"), num(Number::from("12.5")));
+        assert_eq!(tokenizer.tokenize(".5"), num(Number::from(".5")));
+        assert_eq!(
+            tokenizer.tokenize("1.2e-3"),
+            num(Number::scientific("1.2", "-3"))
+        );
+        assert_eq!(
+            tokenizer.tokenize("6.02E+23"),
+            num(Number::scientific("6.02", "23"))
+        );
+        for input in ["1.2 * 10^-3", "1.2 × 10^(-3)", "1.2×10⁻³"] {
+            assert_eq!(
+                tokenizer.tokenize(input),
+                num(Number::scientific("1.2", "-3")),
+                "{}",
+                input
+            );
+        }
+        // x could be a variable, so it isn't a times sign
+        assert_eq!(
+            tokenizer.tokenize("3 x 10^2"),
+            vec![
+                Token::Expr(AST::Number("3".into())),
+                Token::Operand(Symbol::from("x")),
+                Token::Expr(AST::Number("10".into())),
+                Token::Operator(operators::POWER.clone()),
+                Token::Expr(AST::Number("2".into())),
+                Token::End
+            ]
+        );
+        // an e without an exponent after it is just e, and digits after a letter are part of a name
+        assert_eq!(
+            tokenizer.tokenize("2e x2"),
+            vec![
+                Token::Expr(AST::Number("2".into())),
+                Token::Operand(Symbol::from("e")),
+                Token::Operand(Symbol::from("x2")),
+                Token::End
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mixed_numbers() {
+        let tokenizer = Tokenizer::default();
+        let mixed = |whole: Option<&str>, numerator: &str, denominator: &str| {
+            vec![
+                Token::Expr(AST::MixedNumber(
+                    whole.map(String::from),
+                    numerator.into(),
+                    denominator.into(),
+                )),
+                Token::End,
+            ]
+        };
+        assert_eq!(tokenizer.tokenize("1 1/2"), mixed(Some("1"), "1", "2"));
+        assert_eq!(tokenizer.tokenize("1½"), mixed(Some("1"), "1", "2"));
+        assert_eq!(tokenizer.tokenize("½"), mixed(None, "1", "2"));
+        assert_eq!(tokenizer.tokenize("3⁵⁄₁₂"), mixed(Some("3"), "5", "12"));
+        // improper fractions and fractions with spaces in them are products
+        assert_ne!(tokenizer.tokenize("2 3/2"), mixed(Some("2"), "3", "2"));
+        assert_ne!(tokenizer.tokenize("2 1 / 2"), mixed(Some("2"), "1", "2"));
+        // a script ends at its number, so what comes after it is another number
+        let num = |digits: &str| Token::Expr(AST::Number(digits.into()));
+        for (input, script) in [
+            ("x^2 1/3", &*operators::POWER),
+            ("x_2 1/3", &*operators::SUBSCRIPT),
+        ] {
+            assert_eq!(
+                tokenizer.tokenize(input),
+                vec![
+                    Token::Operand(Symbol::from("x")),
+                    Token::Operator(script.clone()),
+                    num("2"),
+                    num("1"),
+                    Token::Operator(operators::DIV.clone()),
+                    num("3"),
+                    Token::End
+                ],
+                "{}",
+                input
+            );
+        }
+        assert_eq!(tokenizer.tokenize("x^2½")[2], num("2"));
+        // unless the script is in parentheses
+        assert_eq!(
+            tokenizer.tokenize("x^(2 1/3)")[3],
+            mixed(Some("2"), "1", "3")[0]
+        );
+    }
+
+    #[test]
+    fn test_repeating_decimals() {
+        let tokenizer = Tokenizer::default();
+        let num = |num: Number| vec![Token::Expr(AST::Number(num)), Token::End];
+        for input in ["0.(3)", "0.3̅", "0.33...", "0.333…"] {
+            assert_eq!(
+                tokenizer.tokenize(input),
+                num(Number::repeating("0.", "3")),
+                "{}",
+                input
+            );
+        }
+        for input in ["0.1(6)", "0.16̅", "0.1666..."] {
+            assert_eq!(
+                tokenizer.tokenize(input),
+                num(Number::repeating("0.1", "6")),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            tokenizer.tokenize("0.1212..."),
+            num(Number::repeating("0.", "12"))
+        );
+        assert_eq!(
+            tokenizer.tokenize("0.142857142857..."),
+            num(Number::repeating("0.", "142857"))
+        );
+        // digits that don't repeat before an ellipsis are just digits that were cut off, even if
+        // there's only one of them
+        for (input, digits) in [
+            ("3.14159...", "3.14159"),
+            ("3.1...", "3.1"),
+            ("0.3...", "0.3"),
+        ] {
+            assert_eq!(
+                tokenizer.tokenize(input),
+                vec![
+                    Token::Expr(AST::Number(digits.into())),
+                    Token::Operand(symbols::LDOTS.clone()),
+                    Token::End
+                ],
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_locale() {
+        let num = |digits: &str| Token::Expr(AST::Number(digits.into()));
+        let comma = Token::Operator(operators::COMMA.clone());
+        let european = Tokenizer {
+            locale: Locale::decimal_comma(),
+            ..Tokenizer::default()
+        };
+        // a decimal comma
+        assert_eq!(european.tokenize("3,14"), vec![num("3.14"), Token::End]);
+        assert_eq!(
+            european.tokenize("0,5e-3")[0],
+            Token::Expr(AST::Number(Number::scientific("0.5", "-3")))
+        );
+        // thousands separators: 1.000 is a thousand, but 1,000 is one
+        assert_eq!(
+            european.tokenize("1.000.000,5"),
+            vec![num("1000000.5"), Token::End]
+        );
+        assert_eq!(european.tokenize("1.000"), vec![num("1000"), Token::End]);
+        assert_eq!(european.tokenize("1,000"), vec![num("1.000"), Token::End]);
+        let english = Tokenizer {
+            locale: Locale {
+                group_sep: Some(','),
+                ..Locale::default()
+            },
+            ..Tokenizer::default()
+        };
+        assert_eq!(
+            english.tokenize("1,000,000.5"),
+            vec![num("1000000.5"), Token::End]
+        );
+
+        // A comma with a digit right after it is a decimal comma, and anything else separates a
+        // list, as does the locale's own list separator. So f(1,5) has one argument, 1.5, but
+        // f(1, 5) and f(1; 5) have two.
+        let max = Token::Function(symbols::SPECIAL_FUNCS["max"].clone());
+        let lpar = Token::Delim(delimiter::LPAR);
+        let rpar = Token::Delim(delimiter::RPAR);
+        assert_eq!(
+            european.tokenize("max(1,5)"),
+            vec![
+                max.clone(),
+                lpar.clone(),
+                num("1.5"),
+                rpar.clone(),
+                Token::End
+            ]
+        );
+        for input in ["max(1, 5)", "max(1; 5)", "max(1;5)"] {
+            assert_eq!(
+                european.tokenize(input),
+                vec![
+                    max.clone(),
+                    lpar.clone(),
+                    num("1"),
+                    comma.clone(),
+                    num("5"),
+                    rpar.clone(),
+                    Token::End
+                ],
+                "{}",
+                input
+            );
+        }
+        // with a decimal point, a comma always separates a list
+        assert_eq!(
+            Tokenizer::default().tokenize("max(1,5)"),
+            vec![max, lpar, num("1"), comma, num("5"), rpar, Token::End]
+        );
+    }
+
+    #[test]
+    fn test_identifiers() {
+        let tokenizer = Tokenizer {
+            split_letters: true,
+            words: vec!["dx".into(), "rate".into()],
+            subscript_digits: true,
+            ..Tokenizer::default()
+        };
+        let sym = |name: &str| Token::Operand(Symbol::from(name));
+        let subscript = |digits: &str| {
+            vec![
+                Token::Operator(operators::SUBSCRIPT.clone()),
+                Token::Expr(AST::Number(digits.into())),
+            ]
+        };
+        assert_eq!(
+            tokenizer.tokenize("abc"),
+            vec![sym("a"), sym("b"), sym("c"), Token::End]
+        );
+        // digits after letters mean the same thing as a subscript
+        assert_eq!(tokenizer.tokenize("x2"), tokenizer.tokenize("x_2"));
+        assert_eq!(
+            tokenizer.tokenize("xy12"),
+            [vec![sym("x"), sym("y")], subscript("12"), vec![Token::End]].concat()
+        );
+        // whitelisted words stay whole wherever they are in a run, longest first
+        assert_eq!(
+            tokenizer.tokenize("rate2 dx xdx ratex"),
+            [
+                vec![sym("rate")],
+                subscript("2"),
+                vec![
+                    sym("dx"),
+                    sym("x"),
+                    sym("dx"),
+                    sym("rate"),
+                    sym("x"),
+                    Token::End
+                ]
+            ]
+            .concat()
+        );
+        let longest = Tokenizer {
+            words: vec!["d".into(), "dx".into()],
+            ..tokenizer.clone()
+        };
+        assert_eq!(
+            longest.tokenize("xdxd"),
+            vec![sym("x"), sym("dx"), sym("d"), Token::End]
+        );
+        // functions come first, and piecewise words stay whole only on their own
+        assert_eq!(
+            tokenizer.tokenize("sinx"),
+            Tokenizer::default().tokenize("sin x")
+        );
+        assert_eq!(tokenizer.tokenize("if"), vec![sym("if"), Token::End]);
+        assert_eq!(
+            tokenizer.tokenize("ifx diff"),
+            vec![
+                sym("i"),
+                sym("f"),
+                sym("x"),
+                sym("d"),
+                sym("i"),
+                sym("f"),
+                sym("f"),
+                Token::End
+            ]
+        );
+        // anything that isn't letters and digits is left alone
+        assert_eq!(tokenizer.tokenize("a.b"), vec![sym("a.b"), Token::End]);
+
+        // either option works on its own
+        let split_only = Tokenizer {
+            subscript_digits: false,
+            ..tokenizer.clone()
+        };
+        assert_eq!(
+            split_only.tokenize("ab2"),
+            vec![sym("a"), sym("b2"), Token::End]
+        );
+        let subscript_only = Tokenizer {
+            split_letters: false,
+            ..tokenizer
+        };
+        assert_eq!(
+            subscript_only.tokenize("ab2"),
+            [vec![sym("ab")], subscript("2"), vec![Token::End]].concat()
+        );
+    }
 }
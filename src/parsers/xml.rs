@@ -0,0 +1,293 @@
+//! A small XML reader, just big enough for MathML. It reads elements, attributes, text, comments,
+//! and CDATA, and it decodes the standard entities plus the named entities that show up in MathML.
+//! It doesn't validate anything or deal with DTDs.
+
+use crate::parsers::ascii::ParseError;
+
+/// A node in an XML document: either an element or some text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlNode {
+    /// An element, like `<mi>x</mi>`.
+    Element(XmlElement),
+    /// Text between tags, with entities already decoded.
+    Text(String),
+}
+
+/// An XML element with its attributes and children.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlElement {
+    /// The name of the element, without any namespace prefix: `m:mi` is just `mi`.
+    pub name: String,
+    /// The attributes, in the order they were written.
+    pub attrs: Vec<(String, String)>,
+    /// The child nodes.
+    pub children: Vec<XmlNode>,
+}
+
+impl XmlElement {
+    /// Gets the value of an attribute, if it exists.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(attr, _)| attr == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Gets the child elements, skipping any text in between them.
+    pub fn child_elements(&self) -> Vec<&XmlElement> {
+        self.children
+            .iter()
+            .filter_map(|child| match child {
+                XmlNode::Element(elem) => Some(elem),
+                XmlNode::Text(_) => None,
+            })
+            .collect()
+    }
+
+    /// Gets all of the text inside the element, with surrounding whitespace removed.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for child in self.children.iter() {
+            match child {
+                XmlNode::Element(elem) => text.push_str(&elem.text()),
+                XmlNode::Text(t) => text.push_str(t),
+            }
+        }
+        text.trim().to_string()
+    }
+}
+
+/// The five entities that XML defines, plus the named entities that MathML commonly uses.
+const ENTITIES: [(&str, char); 27] = [
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{a0}'),
+    ("ApplyFunction", '\u{2061}'),
+    ("af", '\u{2061}'),
+    ("InvisibleTimes", '\u{2062}'),
+    ("it", '\u{2062}'),
+    ("InvisibleComma", '\u{2063}'),
+    ("ic", '\u{2063}'),
+    ("minus", '−'),
+    ("plus", '+'),
+    ("PlusMinus", '±'),
+    ("pm", '±'),
+    ("times", '×'),
+    ("sdot", '·'),
+    ("middot", '·'),
+    ("divide", '÷'),
+    ("le", '≤'),
+    ("leq", '≤'),
+    ("ge", '≥'),
+    ("geq", '≥'),
+    ("ne", '≠'),
+    ("infin", '∞'),
+    ("radic", '√'),
+];
+
+/// Replaces entities like `&lt;` and `&#x3B1;` with the characters they stand for.
+fn decode_entities(text: &str) -> Result<String, ParseError> {
+    let mut decoded = String::new();
+    let mut rest = text;
+    while let Some(i) = rest.find('&') {
+        decoded.push_str(&rest[..i]);
+        rest = &rest[i + 1..];
+        let end = rest.find(';').ok_or(ParseError::InvalidMarkup)?;
+        let name = &rest[..end];
+        let c = if let Some(hex) = name.strip_prefix("#x") {
+            u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+        } else if let Some(dec) = name.strip_prefix('#') {
+            dec.parse().ok().and_then(char::from_u32)
+        } else {
+            ENTITIES
+                .iter()
+                .find(|(entity, _)| *entity == name)
+                .map(|(_, c)| *c)
+        };
+        decoded.push(c.ok_or(ParseError::InvalidMarkup)?);
+        rest = &rest[end + 1..];
+    }
+    decoded.push_str(rest);
+    Ok(decoded)
+}
+
+/// Removes a namespace prefix from a name.
+fn local_name(name: &str) -> &str {
+    match name.rfind(':') {
+        Some(i) => &name[i + 1..],
+        None => name,
+    }
+}
+
+/// A cursor over XML input.
+struct Reader<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Reader<'a> {
+    /// Removes the given prefix, failing if it isn't there.
+    fn expect(&mut self, prefix: &str) -> Result<(), ParseError> {
+        match self.rest.strip_prefix(prefix) {
+            Some(rest) => {
+                self.rest = rest;
+                Ok(())
+            }
+            None => Err(ParseError::InvalidMarkup),
+        }
+    }
+
+    /// Takes everything up to (and not including) the given pattern, and skips the pattern.
+    fn take_until(&mut self, pattern: &str) -> Result<&'a str, ParseError> {
+        let i = self.rest.find(pattern).ok_or(ParseError::InvalidMarkup)?;
+        let taken = &self.rest[..i];
+        self.rest = &self.rest[i + pattern.len()..];
+        Ok(taken)
+    }
+
+    /// Takes a name, for an element or an attribute.
+    fn take_name(&mut self) -> Result<&'a str, ParseError> {
+        let end = self
+            .rest
+            .find(|c: char| c.is_whitespace() || "/>=".contains(c))
+            .unwrap_or(self.rest.len());
+        if end == 0 {
+            return Err(ParseError::InvalidMarkup);
+        }
+        let name = &self.rest[..end];
+        self.rest = &self.rest[end..];
+        Ok(name)
+    }
+
+    /// Skips whitespace.
+    fn skip_whitespace(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    /// Skips whitespace, comments, processing instructions like `<?xml ... ?>`, and doctypes.
+    fn skip_misc(&mut self) -> Result<(), ParseError> {
+        loop {
+            self.skip_whitespace();
+            if self.rest.starts_with("<!--") {
+                self.take_until("-->")?;
+            } else if self.rest.starts_with("<?") {
+                self.take_until("?>")?;
+            } else if self.rest.starts_with("<!") {
+                self.take_until(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Reads an element, starting at its opening `<`.
+    fn read_element(&mut self) -> Result<XmlElement, ParseError> {
+        self.expect("<")?;
+        let full_name = self.take_name()?;
+        let mut elem = XmlElement {
+            name: local_name(full_name).to_string(),
+            attrs: vec![],
+            children: vec![],
+        };
+
+        // attributes
+        loop {
+            self.skip_whitespace();
+            if self.rest.starts_with("/>") {
+                self.rest = &self.rest[2..];
+                return Ok(elem);
+            } else if self.rest.starts_with('>') {
+                self.rest = &self.rest[1..];
+                break;
+            }
+            let attr = local_name(self.take_name()?).to_string();
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let quote = if self.rest.starts_with('"') {
+                "\""
+            } else {
+                "'"
+            };
+            self.expect(quote)?;
+            let value = decode_entities(self.take_until(quote)?)?;
+            elem.attrs.push((attr, value));
+        }
+
+        // children
+        loop {
+            if self.rest.starts_with("</") {
+                self.rest = &self.rest[2..];
+                let closing = self.take_name()?;
+                if closing != full_name {
+                    return Err(ParseError::InvalidMarkup);
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                return Ok(elem);
+            } else if self.rest.starts_with("<!--") {
+                self.take_until("-->")?;
+            } else if self.rest.starts_with("<![CDATA[") {
+                self.rest = &self.rest["<![CDATA[".len()..];
+                let text = self.take_until("]]>")?;
+                elem.children.push(XmlNode::Text(text.to_string()));
+            } else if self.rest.starts_with('<') {
+                elem.children.push(XmlNode::Element(self.read_element()?));
+            } else if self.rest.is_empty() {
+                // ran out of input before the closing tag
+                return Err(ParseError::InvalidMarkup);
+            } else {
+                let end = self.rest.find('<').unwrap_or(self.rest.len());
+                let text = decode_entities(&self.rest[..end])?;
+                self.rest = &self.rest[end..];
+                elem.children.push(XmlNode::Text(text));
+            }
+        }
+    }
+}
+
+/// Reads an XML document, returning its root element.
+pub fn read_xml(input: &str) -> Result<XmlElement, ParseError> {
+    let mut reader = Reader { rest: input };
+    reader.skip_misc()?;
+    let root = reader.read_element()?;
+    reader.skip_misc()?;
+    if reader.rest.is_empty() {
+        Ok(root)
+    } else {
+        Err(ParseError::InvalidMarkup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_xml() {
+        let root = read_xml(
+            "<?xml version=\"1.0\"?>\n<m:math display='block'><!-- hi --><mi>x</mi>\
+             <mo>&lt;</mo><mn>&#x31;</mn><mspace/></m:math>",
+        )
+        .unwrap();
+        assert_eq!(root.name, "math");
+        assert_eq!(root.attr("display"), Some("block"));
+        let children = root.child_elements();
+        assert_eq!(
+            children.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+            vec!["mi", "mo", "mn", "mspace"]
+        );
+        assert_eq!(children[1].text(), "<");
+        assert_eq!(children[2].text(), "1");
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert!(read_xml("<mrow><mi>x</mi>").is_err());
+        assert!(read_xml("<mrow><mi>x</mo></mrow>").is_err());
+        assert!(read_xml("<mi>&bogus;</mi>").is_err());
+        assert!(read_xml("<mi>x</mi><mi>y</mi>").is_err());
+    }
+}
@@ -19,13 +19,26 @@ use strum_macros::{Display, EnumIter, EnumProperty, EnumString};
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct SpecialFunction(String);
 
+/// The special functions that LaTeX doesn't have a command for, which are written with
+/// `\operatorname`.
+const OPERATORNAME_FUNCS: [&str; 2] = ["sgn", "lcm"];
+
 impl SpecialFunction {
+    /// Produces the LaTeX for the name of the function.
+    fn latex(&self) -> String {
+        if OPERATORNAME_FUNCS.contains(&self.0.as_str()) {
+            format!("\\operatorname{{{}}}", self.0)
+        } else {
+            format!("\\{}", self.0)
+        }
+    }
+
     /// Produces a symbol for the square of the function.
     fn square(&self) -> Symbol {
         Symbol {
             unicode_repr: format!("{}²", self.0),
             ascii_repr: format!("{}^2", self.0),
-            latex_repr: format!("\\{}^2", self.0),
+            latex_repr: format!("{}^2", self.latex()),
             other_reprs: vec![],
         }
     }
@@ -35,7 +48,7 @@ impl SpecialFunction {
         Symbol {
             unicode_repr: format!("{}⁻¹", self.0),
             ascii_repr: format!("{}^-1", self.0),
-            latex_repr: format!("\\{}^{{-1}}", self.0),
+            latex_repr: format!("{}^{{-1}}", self.latex()),
             other_reprs: vec![],
         }
     }
@@ -46,7 +59,7 @@ impl From<SpecialFunction> for Symbol {
         Symbol {
             unicode_repr: func.0.clone(),
             ascii_repr: func.0.clone(),
-            latex_repr: format!("\\{}", func.0),
+            latex_repr: func.latex(),
             other_reprs: vec![],
         }
     }
@@ -172,6 +185,115 @@ impl From<&str> for Symbol {
     }
 }
 
+/// Characters that have Unicode superscript forms. Unicode doesn't have a superscript for every
+/// letter, so only the ones that exist are included.
+const SUPERSCRIPT_BASES: &str = "0123456789+-=()abcdefghijklmnoprstuvwxyzT−";
+/// The superscript forms of `SUPERSCRIPT_BASES`, in the same order.
+const SUPERSCRIPT_FORMS: &str = "⁰¹²³⁴⁵⁶⁷⁸⁹⁺⁻⁼⁽⁾ᵃᵇᶜᵈᵉᶠᵍʰⁱʲᵏˡᵐⁿᵒᵖʳˢᵗᵘᵛʷˣʸᶻᵀ⁻";
+
+/// Characters that have Unicode subscript forms. As with superscripts, most letters are missing.
+const SUBSCRIPT_BASES: &str = "0123456789+-=()aehijklmnoprstuvxβγ−";
+/// The subscript forms of `SUBSCRIPT_BASES`, in the same order.
+const SUBSCRIPT_FORMS: &str = "₀₁₂₃₄₅₆₇₈₉₊₋₌₍₎ₐₑₕᵢⱼₖₗₘₙₒₚᵣₛₜᵤᵥₓᵦᵧ₋";
+
+/// Looks up a character in one string of a pair of parallel strings, returning the matching
+/// character in the other.
+fn convert_char(c: char, from: &str, to: &str) -> Option<char> {
+    from.chars()
+        .zip(to.chars())
+        .find(|(f, _)| *f == c)
+        .map(|(_, t)| t)
+}
+
+/// Converts a character to its superscript form, if it has one.
+pub fn to_superscript(c: char) -> Option<char> {
+    convert_char(c, SUPERSCRIPT_BASES, SUPERSCRIPT_FORMS)
+}
+
+/// Converts a superscript character back to its normal form. Returns None if the character isn't a
+/// superscript.
+pub fn from_superscript(c: char) -> Option<char> {
+    convert_char(c, SUPERSCRIPT_FORMS, SUPERSCRIPT_BASES)
+}
+
+/// Converts a character to its subscript form, if it has one.
+pub fn to_subscript(c: char) -> Option<char> {
+    convert_char(c, SUBSCRIPT_BASES, SUBSCRIPT_FORMS)
+}
+
+/// Converts a subscript character back to its normal form. Returns None if the character isn't a
+/// subscript.
+pub fn from_subscript(c: char) -> Option<char> {
+    convert_char(c, SUBSCRIPT_FORMS, SUBSCRIPT_BASES)
+}
+
+/// The Unicode vulgar fractions, with their numerators and denominators.
+const VULGAR_FRACTIONS: [(char, &str, &str); 18] = [
+    ('½', "1", "2"),
+    ('⅓', "1", "3"),
+    ('⅔', "2", "3"),
+    ('¼', "1", "4"),
+    ('¾', "3", "4"),
+    ('⅕', "1", "5"),
+    ('⅖', "2", "5"),
+    ('⅗', "3", "5"),
+    ('⅘', "4", "5"),
+    ('⅙', "1", "6"),
+    ('⅚', "5", "6"),
+    ('⅐', "1", "7"),
+    ('⅛', "1", "8"),
+    ('⅜', "3", "8"),
+    ('⅝', "5", "8"),
+    ('⅞', "7", "8"),
+    ('⅑', "1", "9"),
+    ('⅒', "1", "10"),
+];
+
+/// Gets the vulgar fraction character for a fraction, like ½ for 1/2, if there is one.
+pub fn to_vulgar_fraction(numerator: &str, denominator: &str) -> Option<char> {
+    VULGAR_FRACTIONS
+        .iter()
+        .find(|(_, n, d)| *n == numerator && *d == denominator)
+        .map(|(c, _, _)| *c)
+}
+
+/// Writes a fraction the way the tokenizer reads it as a number of its own: as a vulgar fraction
+/// if there is one, like ½, and with digit scripts otherwise, like ⁵⁄₇.
+pub fn unicode_fraction(numerator: &str, denominator: &str) -> String {
+    match to_vulgar_fraction(numerator, denominator) {
+        Some(c) => c.to_string(),
+        None => format!(
+            "{}⁄{}",
+            numerator
+                .chars()
+                .filter_map(to_superscript)
+                .collect::<String>(),
+            denominator
+                .chars()
+                .filter_map(to_subscript)
+                .collect::<String>()
+        ),
+    }
+}
+
+/// Reads a vulgar fraction character like ½, returning its numerator and denominator. Returns None
+/// if the character isn't one.
+pub fn from_vulgar_fraction(c: char) -> Option<(&'static str, &'static str)> {
+    VULGAR_FRACTIONS
+        .iter()
+        .find(|(f, _, _)| *f == c)
+        .map(|(_, n, d)| (*n, *d))
+}
+
+/// Writes some number of primes using the single characters for up to three of them: ′, ″, and ‴.
+pub fn primes(count: u32) -> String {
+    match count {
+        2 => "″".to_string(),
+        3 => "‴".to_string(),
+        n => PRIME.unicode_repr.repeat(n as usize),
+    }
+}
+
 lazy_static! {
     /// All of the Greek letters, as Symbols that intelligently parse and display. They are keyed by
     /// their ASCII representation, which is capitalized if the letter is uppercase and lowercase
@@ -222,7 +344,8 @@ lazy_static! {
             "sinh", "cosh", "tanh", "coth",
             "max", "min",
             "Pr",
-            "gcd",
+            "gcd", "lcm",
+            "sgn",
             "det", "dim", "ker",
             "inf", "sup"
         ];
@@ -245,40 +368,92 @@ lazy_static! {
     completion.
      */
 
+    /// The = (equals) symbol.
+    pub static ref EQ: Symbol = Symbol::new("=", "=", "=", vec![]);
+    /// The < (less than) symbol.
+    pub static ref LT: Symbol = Symbol::new("<", "<", "<", vec![]);
+    /// The > (greater than) symbol.
+    pub static ref GT: Symbol = Symbol::new(">", ">", ">", vec![]);
     /// The ≤ (less than or equal to) symbol.
-    pub static ref LE: Symbol = Symbol::new("≤", "<=", r"\le", vec![" le"]);
+    pub static ref LE: Symbol = Symbol::new("≤", "<=", r"\le", vec![" le", r"\leq"]);
     /// The ≥ (greater than or equal to) symbol.
-    pub static ref GE: Symbol = Symbol::new("≥", ">=", r"\ge", vec![" ge"]);
+    pub static ref GE: Symbol = Symbol::new("≥", ">=", r"\ge", vec![" ge", r"\geq"]);
     /// The ≠ (not equal to) symbol.
-    pub static ref NEQ: Symbol = Symbol::new("≠", "!=", r"\neq", vec!["=/=", "/=", "neq"]);
+    pub static ref NEQ: Symbol =
+        Symbol::new("≠", "!=", r"\neq", vec!["=/=", "/=", "neq", r"\ne"]);
     /// The + symbol.
     pub static ref PLUS: Symbol = Symbol::new("+", "+", "+", vec!["plus"]);
     /// The - symbol.
-    pub static ref MINUS: Symbol = Symbol::new("-", "−", "-", vec!["minus"]);
+    pub static ref MINUS: Symbol = Symbol::new("−", "-", "-", vec!["minus"]);
     /// The ± (plus or minus) symbol.
     pub static ref PM: Symbol = Symbol::new("±", "+/-", r"\pm", vec!["+-", "pm"]);
     /// The exponentiation symbol. This is not the binary XOR function, and is
     /// also not used generically: exponentiation is special-cased.
     pub static ref POWER: Symbol = Symbol::new("^", "^", r"\^{}", vec![]);
+    /// The subscript symbol. Like exponentiation, subscripts are special-cased.
+    pub static ref SUBSCRIPT: Symbol = Symbol::new("_", "_", r"\_", vec![]);
 
     /// The division symbol. This is not the set difference or quotient group,
     /// and generally using fractions is preferred.
-    pub static ref DIV: Symbol = Symbol::new("/", "/", r"/", vec![]);
+    pub static ref DIV: Symbol = Symbol::new("/", "/", r"/", vec![r"\div", "÷"]);
 
     // The ∞ (infinity) symbol.
-    pub static ref INF: Symbol = Symbol::new("∞", " inf", r"\infty", vec!["infinity", "oo"]);
+    // inf on its own is the infimum, so the ASCII form is oo like in AsciiMath
+    pub static ref INF: Symbol = Symbol::new("∞", "oo", r"\infty", vec!["infinity", " inf"]);
     /// The ∈ (element of) symbol.
     // the question is whether to add E here so a E A becomes a ∈ A. I think it's about 50/50 in the
     // server on whether people do this or not, so I've left it out.
-    pub static ref ELEM: Symbol = Symbol::new("∈", " in", r"\in", vec![" elem"]);
+    pub static ref ELEM: Symbol = Symbol::new("∈", "in", r"\in", vec!["elem"]);
+    /// The ∉ (not an element of) symbol.
+    pub static ref NOT_ELEM: Symbol = Symbol::new("∉", "notin", r"\notin", vec![]);
+    /// The ⊆ (subset) symbol.
+    pub static ref SUBSET: Symbol = Symbol::new("⊆", "subset", r"\subseteq", vec!["subseteq"]);
+    /// The ⊂ (proper subset) symbol. LaTeX's \subset is sometimes used for any subset, but it looks
+    /// like this one, so that's how it's read.
+    pub static ref PROPER_SUBSET: Symbol =
+        Symbol::new("⊂", "subsetneq", r"\subset", vec!["⊊", r"\subsetneq"]);
+    /// The ∪ (union) symbol.
+    // intersect would start with int, which is the integral, so the ASCII forms are short
+    pub static ref UNION: Symbol = Symbol::new("∪", "union", r"\cup", vec!["cup", "uu"]);
+    /// The ∩ (intersection) symbol.
+    pub static ref INTERSECTION: Symbol = Symbol::new("∩", "cap", r"\cap", vec!["nn"]);
+    /// The ∖ (set difference) symbol.
+    pub static ref SETMINUS: Symbol = Symbol::new("∖", "\\", r"\setminus", vec![]);
+    /// The bar in set-builder notation, as in {x | x > 0}.
+    pub static ref MID: Symbol = Symbol::new("|", "|", r"\mid", vec!["∣", ":"]);
+    /// The ∅ (empty set) symbol.
+    pub static ref EMPTY_SET: Symbol = Symbol::new("∅", "O/", r"\emptyset", vec![r"\varnothing"]);
+    /// The ℕ (natural numbers) symbol.
+    pub static ref NATURALS: Symbol = Symbol::new("ℕ", "NN", r"\mathbb{N}", vec![]);
+    /// The ℤ (integers) symbol.
+    pub static ref INTEGERS: Symbol = Symbol::new("ℤ", "ZZ", r"\mathbb{Z}", vec![]);
+    /// The ℚ (rational numbers) symbol.
+    pub static ref RATIONALS: Symbol = Symbol::new("ℚ", "QQ", r"\mathbb{Q}", vec![]);
+    /// The ℝ (real numbers) symbol.
+    pub static ref REALS: Symbol = Symbol::new("ℝ", "RR", r"\mathbb{R}", vec![]);
+    /// The ℂ (complex numbers) symbol.
+    pub static ref COMPLEXES: Symbol = Symbol::new("ℂ", "CC", r"\mathbb{C}", vec![]);
+    /// The … (ellipsis) symbol, for the terms left out of a list or the digits cut off a decimal.
+    pub static ref LDOTS: Symbol = Symbol::new("…", "...", r"\ldots", vec![r"\dots"]);
+    /// The ⋯ (centered ellipsis) symbol, for the terms left out of a sum or product.
+    pub static ref CDOTS: Symbol = Symbol::new("⋯", "cdots", r"\cdots", vec![]);
     /// The ∼ (distributed as) symbol.
-    pub static ref SYM: Symbol = Symbol::new("∼", "~", r"\sym", vec![]);
+    pub static ref SYM: Symbol = Symbol::new("∼", "~", r"\sim", vec![]);
     /// The ≅ (approximately equal to) symbol.
     pub static ref APPROX: Symbol = Symbol::new("≅", "~=", r"\approx", vec![]);
     /// The multiplication symbol, using a dot instead of the times operator.
-    pub static ref MULT: Symbol = Symbol::new("·", "*", r"\cdot", vec![" times", "\times", "×"]);
+    pub static ref MULT: Symbol = Symbol::new("·", "*", r"\cdot", vec![" times", r"\times", "×"]);
     /// The ° (degrees) symbol.
-    pub static ref DEGREE: Symbol = Symbol::new("°", "o", r"^{\circ}", vec!["deg", "degrees"]);
+    // o on its own is a variable, so the ASCII form is deg
+    pub static ref DEGREE: Symbol = Symbol::new("°", "deg", r"^{\circ}", vec!["degrees"]);
+    /// The ! (factorial) symbol.
+    pub static ref FACTORIAL: Symbol = Symbol::new("!", "!", "!", vec![]);
+    /// The !! (double factorial) symbol.
+    pub static ref DOUBLE_FACTORIAL: Symbol = Symbol::new("!!", "!!", "!!", vec!["‼"]);
+    /// The % (percent) symbol.
+    pub static ref PERCENT: Symbol = Symbol::new("%", "%", r"\%", vec![]);
+    /// The transpose symbol, as in Aᵀ.
+    pub static ref TRANSPOSE: Symbol = Symbol::new("ᵀ", "^T", r"^{\top}", vec![]);
     /// The left parenthesis `(``.
     pub static ref LEFT_PAR: Symbol = Symbol::new("(", "(", r"\left(", vec![]);
     /// The right parenthesis `)``.
@@ -287,12 +462,110 @@ lazy_static! {
     pub static ref LEFT_BRACKET: Symbol = Symbol::new("[", "[", r"\left[", vec![]);
     /// The right bracket `]``.
     pub static ref RIGHT_BRACKET: Symbol = Symbol::new("]", "]", r"\right]", vec![]);
+    /// The bar that starts an absolute value, as in |x|.
+    pub static ref LEFT_ABS: Symbol = Symbol::new("|", "|", r"\left|", vec![r"\lvert"]);
+    /// The bar that ends an absolute value.
+    pub static ref RIGHT_ABS: Symbol = Symbol::new("|", "|", r"\right|", vec![r"\rvert"]);
+    /// The double bar that starts a norm, as in ‖v‖.
+    pub static ref LEFT_NORM: Symbol = Symbol::new("‖", "||", r"\lVert", vec![]);
+    /// The double bar that ends a norm.
+    pub static ref RIGHT_NORM: Symbol = Symbol::new("‖", "||", r"\rVert", vec![]);
+    /// The left brace `{`, which starts a set.
+    pub static ref LEFT_BRACE: Symbol = Symbol::new("{", "{", r"\left\{", vec![r"\{"]);
+    /// The right brace `}`, which ends a set.
+    pub static ref RIGHT_BRACE: Symbol = Symbol::new("}", "}", r"\right\}", vec![r"\}"]);
+    /// The left floor bracket, as in ⌊x⌋.
+    pub static ref LEFT_FLOOR: Symbol = Symbol::new("⌊", "floor(", r"\lfloor", vec![]);
+    /// The right floor bracket. In plain text, it's the `)` that closes `floor(`.
+    pub static ref RIGHT_FLOOR: Symbol = Symbol::new("⌋", ")", r"\rfloor", vec![]);
+    /// The left ceiling bracket, as in ⌈x⌉.
+    pub static ref LEFT_CEIL: Symbol = Symbol::new("⌈", "ceil(", r"\lceil", vec![]);
+    /// The right ceiling bracket. In plain text, it's the `)` that closes `ceil(`.
+    pub static ref RIGHT_CEIL: Symbol = Symbol::new("⌉", ")", r"\rceil", vec![]);
+    /// The left angle bracket, as in ⟨a, b⟩.
+    pub static ref LEFT_ANGLE: Symbol = Symbol::new("⟨", "<<", r"\langle", vec!["〈"]);
+    /// The right angle bracket.
+    pub static ref RIGHT_ANGLE: Symbol = Symbol::new("⟩", ">>", r"\rangle", vec!["〉"]);
+    /// The start of an invisible group, like LaTeX's `{`. When it has to be shown it's shown as a
+    /// parenthesis.
+    pub static ref LEFT_GROUP: Symbol = Symbol::new("(", "(", "{", vec![]);
+    /// The end of an invisible group, like LaTeX's `}`.
+    pub static ref RIGHT_GROUP: Symbol = Symbol::new(")", ")", "}", vec![]);
+    /// The √ (square root) symbol.
+    pub static ref SQRT: Symbol = Symbol::new("√", "sqrt", r"\sqrt", vec![]);
+    /// The ∛ (cube root) symbol.
+    pub static ref CBRT: Symbol = Symbol::new("∛", "cbrt", r"\sqrt[3]", vec![]);
+    /// The ∜ (fourth root) symbol.
+    pub static ref FOURTH_ROOT: Symbol = Symbol::new("∜", "root4", r"\sqrt[4]", vec![]);
+    /// The general root, written as a function of the index and the radicand: `root(3, x)`.
+    pub static ref ROOT: Symbol = Symbol::new("root", "root", r"\sqrt", vec![]);
+
+    /// The ∑ (summation) symbol.
+    pub static ref SUM: Symbol = Symbol::new("∑", "sum", r"\sum", vec![]);
+    /// The ∏ (product) symbol.
+    pub static ref PROD: Symbol = Symbol::new("∏", "prod", r"\prod", vec![]);
+    /// The ∫ (integral) symbol.
+    pub static ref INT: Symbol = Symbol::new("∫", "int", r"\int", vec![]);
+    /// The limit symbol.
+    pub static ref LIM: Symbol = Symbol::new("lim", "lim", r"\lim", vec![]);
+    /// The → (tends to) symbol, as in limits.
+    pub static ref TO: Symbol = Symbol::new("→", "->", r"\to", vec![r"\rightarrow"]);
+    /// The ∂ (partial derivative) symbol.
+    pub static ref PARTIAL: Symbol = Symbol::new("∂", "partial", r"\partial", vec![]);
+    /// The ′ (prime) symbol, as in f′ for the derivative of f.
+    pub static ref PRIME: Symbol = Symbol::new("′", "'", "'", vec![]);
+    /// The ∧ (logical and) symbol.
+    pub static ref AND: Symbol = Symbol::new("∧", "and", r"\land", vec!["&&", r"\wedge"]);
+    /// The ∨ (logical or) symbol.
+    pub static ref OR: Symbol = Symbol::new("∨", "or", r"\lor", vec!["||", r"\vee"]);
+    /// The ⊕ (exclusive or) symbol.
+    pub static ref XOR: Symbol = Symbol::new("⊕", "xor", r"\oplus", vec![]);
+    /// The ¬ (logical not) symbol.
+    // ! is also the factorial, but that comes after its argument instead of before it
+    pub static ref NOT: Symbol = Symbol::new("¬", "not", r"\neg", vec!["!", r"\lnot"]);
+    /// The ⇒ (implies) symbol.
+    pub static ref IMPLIES: Symbol = Symbol::new("⇒", "=>", r"\implies", vec![r"\Rightarrow"]);
+    /// The ⇔ (if and only if) symbol.
+    pub static ref IFF: Symbol = Symbol::new("⇔", "<=>", r"\iff", vec![r"\Leftrightarrow"]);
+    /// The ∀ (for all) symbol.
+    pub static ref FORALL: Symbol = Symbol::new("∀", "forall", r"\forall", vec![]);
+    /// The ∃ (there exists) symbol.
+    pub static ref EXISTS: Symbol = Symbol::new("∃", "exists", r"\exists", vec![]);
+    /// Juxtaposition, which isn't written with any symbol at all.
+    pub static ref CONCAT: Symbol = Symbol::new("", "", "", vec![]);
 
     // The comma symbol, needed for variadic functions.
     pub static ref COMMA: Symbol = Symbol::from(",");
+    /// The semicolon, which separates the rows of a matrix, as in [1, 2; 3, 4].
+    pub static ref SEMICOLON: Symbol = Symbol::from(";");
+    /// The matrix "function", as in mat(a, b; c, d), which gives its arguments as rows.
+    pub static ref MATRIX: Symbol = Symbol::new("mat", "mat", r"\mat", vec![]);
 
     // TODO add more
 
+    /// The roots, which are written like functions. The fourth root comes before the general root
+    /// so that `root4` isn't read as `root` followed by 4.
+    pub static ref ROOTS: Vec<Symbol> = vec![
+        SQRT.clone(),
+        CBRT.clone(),
+        FOURTH_ROOT.clone(),
+        ROOT.clone(),
+    ];
+
+    /// The big operators, which take bounds and a body: sums, products, integrals, and limits.
+    pub static ref BIG_OPS: Vec<Symbol> = vec![
+        SUM.clone(),
+        PROD.clone(),
+        INT.clone(),
+        LIM.clone(),
+    ];
+
+    /// The quantifiers, which bind a variable in the statement after them.
+    pub static ref QUANTIFIERS: Vec<Symbol> = vec![
+        FORALL.clone(),
+        EXISTS.clone(),
+    ];
+
     /// The delimiters.
     pub static ref DELIMS: Vec<Symbol> = {
         vec![
@@ -310,11 +583,20 @@ lazy_static! {
             NEQ.clone(),
             PM.clone(),
             INF.clone(),
-            ELEM.clone(),
             SYM.clone(),
             APPROX.clone(),
             MULT.clone(),
-            DEGREE.clone(),
+            PARTIAL.clone(),
+            FORALL.clone(),
+            EXISTS.clone(),
+            EMPTY_SET.clone(),
+            NATURALS.clone(),
+            INTEGERS.clone(),
+            RATIONALS.clone(),
+            REALS.clone(),
+            COMPLEXES.clone(),
+            LDOTS.clone(),
+            CDOTS.clone(),
         ]
     };
 
@@ -332,7 +614,7 @@ lazy_static! {
         for (_k, sym) in SPECIAL_FUNCS.clone().into_iter() {
             symbols.push(sym);
         }
-        symbols.extend_from_slice(&*MISC.as_slice());
+        symbols.extend_from_slice(MISC.as_slice());
         symbols
     };
 }
@@ -351,6 +633,14 @@ mod tests {
         assert_eq!(LATIN_SYMBOLS.len(), 52);
     }
 
+    #[test]
+    fn test_scripts() {
+        assert_eq!(to_superscript('2'), Some('²'));
+        assert_eq!(from_superscript('⁻'), Some('-'));
+        assert_eq!(from_subscript('ᵢ'), Some('i'));
+        assert_eq!(to_subscript('q'), None);
+    }
+
     #[test]
     fn test_greek_letters() {
         let sym1: Symbol = CasedGreekLetter {